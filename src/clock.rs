@@ -0,0 +1,46 @@
+//! Source of the current time for all time based states like the
+//! [`timer`][crate::timer::CreateTimerUi::timer].
+//!
+//! Defaults to [`LocalClock`], but can be replaced through
+//! [`set_clock`][crate::UiStates::set_clock], for example to drive the
+//! states from a simulated time.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Local};
+
+pub trait Clock
+where
+    Self: Send + Sync + 'static,
+{
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// Uses the system time through [`Local::now`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalClock;
+
+impl Clock for LocalClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Cheaply clonable handle to the [`Clock`] of a [`UiStates`][crate::UiStates].
+#[derive(Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    pub fn new(clock: impl Clock) -> Self {
+        Self(Arc::new(clock))
+    }
+    pub fn now(&self) -> DateTime<Local> {
+        self.0.now()
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(LocalClock)
+    }
+}
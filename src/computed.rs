@@ -0,0 +1,171 @@
+//! Memoized values that are only computed again once their dependencies
+//! change.
+//!
+//! ```
+//! // `f64` is not `Hash`, so the tax rate is hashed through its bits
+//! let deps = (&self.items, self.tax_rate.to_bits());
+//! let total = *self.ui.computed("total", deps, || {
+//!     self.items.iter().map(|item| item.price).sum::<f64>() * (1. + self.tax_rate)
+//! });
+//!
+//! // or computed in the background, keeping the previous value until done
+//! let items = self.items.clone();
+//! if let Some(stats) = self.ui.computed_async(ctx, "stats", &self.items, move || async move {
+//!     tokio::task::spawn_blocking(move || expensive_stats(&items))
+//!         .await
+//!         .map_err(|err| BoxedSendError(Box::new(err)))
+//! }) {
+//!     ui.label(format!("{stats:?}"));
+//! }
+//! ```
+//!
+//! Dependencies are compared through their hash, so anything implementing
+//! [`Hash`] works, including tuples of references. A failed background
+//! computation keeps the previous value and queues a
+//! [`StateEvent::FutureCompleted`] with the error.
+
+use std::{
+    any::Any,
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use egui::Context;
+use lazy_async_promise::{BoxedSendError, ImmediateValuePromise};
+
+use crate::{clock::SharedClock, events::StateEvent, future_await::FutureState, UiStates};
+
+pub trait Computed {
+    /// Returns the value of the last call to `compute`, calling it again only
+    /// if the hash of `deps` changed.
+    fn computed<T>(
+        &mut self,
+        name: impl Into<String>,
+        deps: impl Hash,
+        compute: impl FnOnce() -> T,
+    ) -> &T
+    where
+        T: Send + 'static;
+
+    /// Same as [`computed`][Computed::computed] but with the value computed
+    /// by a future. Returns the previous value while the future runs, `None`
+    /// until the first one finished.
+    ///
+    /// Requests a repaint once the future finished.
+    fn computed_async<T, F>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        deps: impl Hash,
+        compute: impl FnOnce() -> F,
+    ) -> Option<&T>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, BoxedSendError>> + Send + 'static;
+}
+
+impl Computed for UiStates {
+    fn computed<T>(
+        &mut self,
+        name: impl Into<String>,
+        deps: impl Hash,
+        compute: impl FnOnce() -> T,
+    ) -> &T
+    where
+        T: Send + 'static,
+    {
+        let hash = hash_of(deps);
+        let state = self.get_mut(name.into(), ComputedState::<T>::default());
+        if state.hash != Some(hash) {
+            state.hash = Some(hash);
+            state.value = None;
+        }
+        state.value.get_or_insert_with(compute)
+    }
+
+    fn computed_async<T, F>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        deps: impl Hash,
+        compute: impl FnOnce() -> F,
+    ) -> Option<&T>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, BoxedSendError>> + Send + 'static,
+    {
+        let name = name.into();
+        let hash = hash_of(deps);
+        self.register_poller(name.clone(), poll_computed_state::<T>);
+        let (state, events) = self.get_mut_with_events(name.clone(), ComputedState::default());
+        if state.hash != Some(hash) {
+            state.hash = Some(hash);
+            let future = compute();
+            let ctx = ctx.clone();
+            state.computing.start(ImmediateValuePromise::new(async move {
+                let result = future.await;
+                ctx.request_repaint();
+                result
+            }));
+        }
+        state.poll(&name, events);
+        state.value.as_ref()
+    }
+}
+
+fn hash_of(deps: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    deps.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct ComputedState<T>
+where
+    T: Send + 'static,
+{
+    /// Hash of the dependencies the value was computed for.
+    hash: Option<u64>,
+    value: Option<T>,
+    /// Background computation of the next value.
+    computing: FutureState<T>,
+}
+
+impl<T> Default for ComputedState<T>
+where
+    T: Send + 'static,
+{
+    fn default() -> Self {
+        Self {
+            hash: None,
+            value: None,
+            computing: FutureState::default(),
+        }
+    }
+}
+
+impl<T> ComputedState<T>
+where
+    T: Send + 'static,
+{
+    /// Replaces the value once the background computation is done.
+    fn poll(&mut self, name: &str, events: &mut Vec<StateEvent>) {
+        self.computing.poll(name, events);
+        if let Some(value) = self.computing.take_value() {
+            self.value = Some(value);
+            self.computing.clear();
+        }
+    }
+}
+
+fn poll_computed_state<T>(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    _: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) where
+    T: Send + 'static,
+{
+    if let Some(state) = state.downcast_mut::<ComputedState<T>>() {
+        state.poll(name, events);
+    }
+}
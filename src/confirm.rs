@@ -0,0 +1,185 @@
+//! Buttons for destructive actions that have to be clicked twice.
+//!
+//! The first click arms the button, which then shows a confirm prompt until
+//! the timeout runs out. Only a click while armed counts as confirmed.
+//!
+//! ```
+//! if self.ui.confirm_button("delete", "delete").show(ui) {
+//!     self.delete_item();
+//! }
+//!
+//! // or asking in a dialog instead
+//! if self.ui.confirm_button("delete_all", "delete all").modal().show(ui) {
+//!     self.delete_all();
+//! }
+//! ```
+
+use chrono::{DateTime, Duration, Local};
+use egui::{Align2, Button, Id, Key, Modifiers, Ui, Window};
+
+use crate::{
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    timer::TimerState,
+    UiStates,
+};
+
+const DEFAULT_TIMEOUT_SECONDS: i64 = 3;
+
+pub trait CreateConfirmButton {
+    fn confirm_button(
+        &mut self,
+        name: impl Into<String>,
+        label: impl Into<String>,
+    ) -> ConfirmButtonBuilder;
+}
+
+impl CreateConfirmButton for UiStates {
+    fn confirm_button(
+        &mut self,
+        name: impl Into<String>,
+        label: impl Into<String>,
+    ) -> ConfirmButtonBuilder {
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let now = self.clock.now();
+        let name = name.into();
+        let id = Id::new(&name);
+        let state = self.get_mut(name, ConfirmState::default());
+        ConfirmButtonBuilder {
+            state,
+            id,
+            now,
+            label: label.into(),
+            modal: false,
+            strings,
+            style,
+        }
+    }
+}
+
+pub struct ConfirmButtonBuilder<'state> {
+    state: &'state mut ConfirmState,
+    id: Id,
+    now: DateTime<Local>,
+    label: String,
+    modal: bool,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+}
+
+impl<'state> ConfirmButtonBuilder<'state> {
+    /// How long the button stays armed after the first click, defaults to
+    /// three seconds. Not used by the [`modal`][ConfirmButtonBuilder::modal]
+    /// variant.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.state.timer.set_duration(timeout);
+        self
+    }
+    /// Asks for confirmation in a dialog instead of on the button itself. The
+    /// dialog stays open until it is answered or closed with escape.
+    #[must_use]
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Returns `true` only for the confirming click.
+    pub fn show(self, ui: &mut Ui) -> bool {
+        if self.modal {
+            self.show_modal(ui)
+        } else {
+            self.show_inline(ui)
+        }
+    }
+    fn show_inline(self, ui: &mut Ui) -> bool {
+        let Self {
+            state,
+            now,
+            label,
+            strings,
+            style,
+            ..
+        } = self;
+        state.timer.finish_if_elapsed(now);
+        let Some(remaining) = state.timer.remaining(now) else {
+            if ui.button(label).clicked() {
+                state.timer.start(now);
+            }
+            return false;
+        };
+
+        ui.ctx()
+            .request_repaint_after(remaining.to_std().unwrap_or_default());
+        let prompt = style.error_text(ui, strings.get(TextKey::ConfirmPrompt));
+        if ui.add(Button::new(prompt)).clicked() {
+            state.timer.stop();
+            true
+        } else {
+            false
+        }
+    }
+    fn show_modal(self, ui: &mut Ui) -> bool {
+        let Self {
+            state,
+            id,
+            label,
+            strings,
+            style,
+            ..
+        } = self;
+        if ui.button(&label).clicked() {
+            state.modal_open = true;
+        }
+        if !state.modal_open {
+            return false;
+        }
+
+        let mut confirmed = false;
+        let mut cancelled = ui.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Escape));
+        Window::new(label)
+            .id(id.with("confirm_modal"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0., 0.])
+            .show(ui.ctx(), |ui| {
+                ui.label(strings.get(TextKey::ConfirmQuestion));
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(style.error_text(ui, strings.get(TextKey::Confirm)))
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button(strings.get(TextKey::Cancel)).clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        if confirmed || cancelled {
+            state.modal_open = false;
+        }
+        confirmed
+    }
+}
+
+struct ConfirmState {
+    /// Runs while the inline button is armed.
+    timer: TimerState,
+    modal_open: bool,
+}
+
+impl Default for ConfirmState {
+    fn default() -> Self {
+        Self {
+            timer: TimerState::from(DEFAULT_TIMEOUT_SECONDS),
+            modal_open: false,
+        }
+    }
+}
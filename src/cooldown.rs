@@ -0,0 +1,99 @@
+//! Buttons that can only be clicked once per cooldown.
+//!
+//! ```
+//! if self
+//!     .ui
+//!     .cooldown_button("resend", "resend email", Duration::seconds(30))
+//!     .show(ui)
+//!     .clicked()
+//! {
+//!     self.resend_email();
+//! }
+//! ```
+//!
+//! The cooldown is stored in the [`UiStates`] and only depends on the time
+//! of the click, so it keeps running while the button is not shown.
+
+use chrono::{DateTime, Duration, Local};
+use egui::{Button, Response, Ui};
+
+use crate::{timer::TimerState, timer_widgets::CountdownFormat, UiStates};
+
+pub trait CreateCooldownButton {
+    fn cooldown_button(
+        &mut self,
+        name: impl Into<String>,
+        label: impl Into<String>,
+        duration: Duration,
+    ) -> CooldownButtonBuilder;
+}
+
+impl CreateCooldownButton for UiStates {
+    fn cooldown_button(
+        &mut self,
+        name: impl Into<String>,
+        label: impl Into<String>,
+        duration: Duration,
+    ) -> CooldownButtonBuilder {
+        let now = self.clock.now();
+        let timer = self.get_mut(name.into(), TimerState::from(duration));
+        timer.set_duration(duration);
+        timer.finish_if_elapsed(now);
+        CooldownButtonBuilder {
+            timer,
+            now,
+            label: label.into(),
+            format: CountdownFormat::default(),
+        }
+    }
+}
+
+pub struct CooldownButtonBuilder<'state> {
+    timer: &'state mut TimerState,
+    now: DateTime<Local>,
+    label: String,
+    format: CountdownFormat,
+}
+
+impl<'state> CooldownButtonBuilder<'state> {
+    /// How the remaining time is appended to the label.
+    #[must_use]
+    pub fn format(mut self, format: CountdownFormat) -> Self {
+        self.format = format;
+        self
+    }
+    /// Starts the cooldown without the button being clicked.
+    #[must_use]
+    pub fn start(self) -> Self {
+        self.timer.start(self.now);
+        self
+    }
+    pub fn is_cooling_down(&self) -> bool {
+        self.timer.is_running()
+    }
+    /// Shows the button, disabled and with the remaining time while cooling
+    /// down. Clicking it starts the cooldown.
+    pub fn show(self, ui: &mut Ui) -> Response {
+        let Self {
+            timer,
+            now,
+            label,
+            format,
+        } = self;
+        let remaining = timer.remaining(now);
+        let text = match remaining {
+            Some(remaining) => format!("{label} ({})", format.format(remaining)),
+            None => label,
+        };
+        let response = ui.add_enabled(remaining.is_none(), Button::new(text));
+        if response.clicked() {
+            timer.start(now);
+        }
+        if let Some(remaining) = remaining {
+            // repaint when the shown seconds change
+            ui.ctx()
+                .request_repaint_after(TimerState::until_next_second(remaining));
+        }
+        response
+    }
+}
@@ -1,7 +1,12 @@
-use egui::{Spinner, Ui};
+use egui::Ui;
 use lazy_async_promise::{ImmediateValuePromise, ImmediateValueState};
 
-use crate::UiStates;
+use crate::{
+    error_ui::ErrorUi,
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    UiStates,
+};
 
 pub trait DefaultCreatePromiseAwait {
     fn default_promise_await<'state, InitUi, PromiseOut>(
@@ -22,9 +27,13 @@ impl DefaultCreatePromiseAwait for UiStates {
         InitUi: InitUiTraits<PromiseOut>,
         PromiseOut: Send + 'static,
     {
+        let strings = self.strings.clone();
+        let style = self.style.clone();
         let state = self.get_mut(name, DefaultPromiseAwaitState::default());
         DefaultPromiseAwaitBuilder {
             internal_state: state,
+            strings,
+            style,
             init_ui: None,
         }
     }
@@ -36,6 +45,8 @@ where
     PromiseOut: Send + 'static,
 {
     internal_state: &'state mut DefaultPromiseAwaitState<PromiseOut>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
     init_ui: Option<InitUi>,
 }
 
@@ -57,9 +68,17 @@ where
         self.init_ui = Some(ui);
         self
     }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
     pub fn show(self, ui: &mut Ui) {
         let Self {
             internal_state,
+            strings,
+            style,
             init_ui: Some(init_ui),
         } = self
         else {
@@ -71,17 +90,35 @@ where
         {
             let state = running_promise.poll_state();
             if matches!(state, ImmediateValueState::Updating) {
-                ui.add(Spinner::new());
+                ui.add(style.spinner());
             } else {
-                ui.label(match state {
-                    ImmediateValueState::Empty => "empty",
-                    ImmediateValueState::Success(_) => "success",
-                    ImmediateValueState::Error(_) => "error",
-                    ImmediateValueState::Updating => unreachable!()
-                }.to_string());
-                if ui.button("reset").clicked() {
+                let mut reset = false;
+                style.with_buttons(
+                    ui,
+                    |ui| match state {
+                        ImmediateValueState::Empty => {
+                            ui.label(strings.get(TextKey::Empty));
+                        }
+                        ImmediateValueState::Success(_) => {
+                            ui.label(style.success_text(ui, strings.get(TextKey::Success)));
+                        }
+                        ImmediateValueState::Error(err) => {
+                            ErrorUi::new(err)
+                                .strings(strings.clone())
+                                .style(style.clone())
+                                .show(ui);
+                        }
+                        ImmediateValueState::Updating => unreachable!(),
+                    },
+                    |ui| {
+                        if ui.button(strings.get(TextKey::Reset)).clicked() {
+                            reset = true;
+                        }
+                    },
+                );
+                if reset {
                     internal_state.clear();
-                } 
+                }
             }
         } else {
             let mut set = |promise| internal_state.set(promise);
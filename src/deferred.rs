@@ -0,0 +1,346 @@
+//! Actions that are only committed if they are not undone in time, for
+//! "Item deleted — Undo" flows.
+//!
+//! ```
+//! if ui.button("delete").clicked() {
+//!     let id = item.id;
+//!     self.ui
+//!         .deferred_action("delete", Duration::seconds(5), move || delete_item(id));
+//! }
+//!
+//! ui.horizontal(|ui| {
+//!     if self.ui.is_deferred("delete") {
+//!         ui.label("Item deleted");
+//!     }
+//!     match self.ui.deferred_action_ui("delete").default().show(ui) {
+//!         Some(DeferredOutcome::Undone) => self.restore_item(),
+//!         Some(DeferredOutcome::Committed) | None => {}
+//!     }
+//! });
+//! ```
+//!
+//! The action is committed once its time is up even if the UI is not shown,
+//! as long as [`UiStates::poll`] or [`UiStates::drain_events`] is called,
+//! which also receive a [`StateEvent::DeferredActionFinished`].
+
+use std::any::Any;
+
+use chrono::{DateTime, Duration, Local};
+use egui::Ui;
+
+use crate::{
+    clock::SharedClock,
+    events::{DeferredOutcome, StateEvent},
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    timer::TimerState,
+    timer_widgets::CountdownFormat,
+    UiStates,
+};
+
+pub trait DeferredActions {
+    /// Stages the action to be committed after `duration`. An action already
+    /// staged under the same name is committed right away.
+    ///
+    /// Whether the action was undone or committed is only known later, it is
+    /// returned once by [`deferred_outcome`][DeferredActions::deferred_outcome]
+    /// or by the [`show`][DeferredActionBuilder::show] of the UI, whichever is
+    /// called first.
+    fn deferred_action(
+        &mut self,
+        name: impl Into<String>,
+        duration: Duration,
+        commit: impl FnOnce() + Send + 'static,
+    );
+
+    /// `true` while an action is staged and can still be undone.
+    fn is_deferred(&mut self, name: impl Into<String>) -> bool;
+
+    /// Returns whether the staged action was undone or committed, once, on
+    /// the first call after that happened.
+    fn deferred_outcome(&mut self, name: impl Into<String>) -> Option<DeferredOutcome>;
+
+    /// Shows the UI for undoing the staged action of that name.
+    #[must_use]
+    fn deferred_action_ui(&mut self, name: impl Into<String>) -> DeferredActionBuilder;
+}
+
+impl DeferredActions for UiStates {
+    fn deferred_action(
+        &mut self,
+        name: impl Into<String>,
+        duration: Duration,
+        commit: impl FnOnce() + Send + 'static,
+    ) {
+        let name = name.into();
+        let now = self.clock.now();
+        self.register_poller(name.clone(), poll_deferred_state);
+        let (state, events) = self.get_mut_with_events(name.clone(), DeferredState::default());
+        state.finish(&name, DeferredOutcome::Committed, events);
+        state.commit = Some(Box::new(commit));
+        state.timer.set_duration(duration);
+        state.timer.start(now);
+    }
+
+    fn is_deferred(&mut self, name: impl Into<String>) -> bool {
+        let name = name.into();
+        let now = self.clock.now();
+        let (state, events) = self.get_mut_with_events(name.clone(), DeferredState::default());
+        state.poll(&name, now, events);
+        state.commit.is_some()
+    }
+
+    fn deferred_outcome(&mut self, name: impl Into<String>) -> Option<DeferredOutcome> {
+        let name = name.into();
+        let now = self.clock.now();
+        let (state, events) = self.get_mut_with_events(name.clone(), DeferredState::default());
+        state.poll(&name, now, events);
+        state.outcome.take()
+    }
+
+    fn deferred_action_ui(&mut self, name: impl Into<String>) -> DeferredActionBuilder {
+        let name = name.into();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let now = self.clock.now();
+        let (state, events) = self.get_mut_with_events(name.clone(), DeferredState::default());
+        state.poll(&name, now, events);
+        DeferredActionBuilder {
+            name,
+            state,
+            events,
+            now,
+            strings,
+            style,
+            pending_ui: None,
+        }
+    }
+}
+
+// gets the style when shown, so it can be overridden after `default`
+type PendingUi = Box<dyn FnOnce(&mut Ui, Duration, &mut dyn FnMut(), &LightStatesStyle)>;
+
+pub struct DeferredActionBuilder<'state> {
+    name: String,
+    state: &'state mut DeferredState,
+    events: &'state mut Vec<StateEvent>,
+    now: DateTime<Local>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    pending_ui: Option<PendingUi>,
+}
+
+impl<'state> DeferredActionBuilder<'state> {
+    /// Remaining time and an undo button.
+    #[must_use]
+    pub fn default(mut self) -> Self {
+        let strings = self.strings.clone();
+        self.pending_ui = Some(Box::new(move |ui, remaining, undo, style| {
+            style.with_buttons(
+                ui,
+                |ui| {
+                    ui.label(CountdownFormat::Seconds.format(remaining));
+                },
+                |ui| {
+                    if ui.button(strings.get(TextKey::Undo)).clicked() {
+                        undo();
+                    }
+                },
+            );
+        }));
+        self
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Shown while the action can be undone. Gets the remaining time and the
+    /// undo function.
+    #[must_use]
+    pub fn pending_ui(
+        mut self,
+        pending_ui: impl FnOnce(&mut Ui, Duration, &mut dyn FnMut()) + 'static,
+    ) -> Self {
+        self.pending_ui = Some(Box::new(move |ui, remaining, undo, _| {
+            pending_ui(ui, remaining, undo)
+        }));
+        self
+    }
+    /// Returns the outcome once, on the first call after the action was
+    /// committed or undone.
+    pub fn show(self, ui: &mut Ui) -> Option<DeferredOutcome> {
+        let Self {
+            name,
+            state,
+            events,
+            now,
+            style,
+            pending_ui,
+            ..
+        } = self;
+        if let Some(remaining) = state
+            .timer
+            .remaining(now)
+            .filter(|_| state.commit.is_some())
+        {
+            let mut undo = false;
+            if let Some(pending_ui) = pending_ui {
+                let mut undo_fn = || undo = true;
+                pending_ui(ui, remaining, &mut undo_fn, &style);
+            }
+            if undo {
+                state.finish(&name, DeferredOutcome::Undone, events);
+            } else {
+                ui.ctx()
+                    .request_repaint_after(remaining.to_std().unwrap_or_default());
+            }
+        }
+        state.outcome.take()
+    }
+}
+
+struct DeferredState {
+    commit: Option<Box<dyn FnOnce() + Send>>,
+    /// Runs while the action is staged.
+    timer: TimerState,
+    /// Kept until it is returned by [`DeferredActionBuilder::show`].
+    outcome: Option<DeferredOutcome>,
+}
+
+impl Default for DeferredState {
+    fn default() -> Self {
+        Self {
+            commit: None,
+            timer: TimerState::from(Duration::zero()),
+            outcome: None,
+        }
+    }
+}
+
+impl DeferredState {
+    /// Commits the action once its time is up.
+    fn poll(&mut self, name: &str, now: DateTime<Local>, events: &mut Vec<StateEvent>) {
+        if self.commit.is_some() && self.timer.finish_if_elapsed(now) {
+            self.finish(name, DeferredOutcome::Committed, events);
+        }
+    }
+    /// Commits or drops the staged action, does nothing if there is none.
+    fn finish(&mut self, name: &str, outcome: DeferredOutcome, events: &mut Vec<StateEvent>) {
+        let Some(commit) = self.commit.take() else {
+            return;
+        };
+        if outcome == DeferredOutcome::Committed {
+            commit();
+        }
+        self.timer.stop();
+        self.outcome = Some(outcome);
+        events.push(StateEvent::DeferredActionFinished {
+            name: name.to_string(),
+            outcome,
+        });
+    }
+}
+
+fn poll_deferred_state(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    clock: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) {
+    if let Some(state) = state.downcast_mut::<DeferredState>() {
+        state.poll(name, clock.now(), events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use chrono::Duration;
+    use egui::Context;
+
+    use super::DeferredActions;
+    use crate::{
+        events::{DeferredOutcome, StateEvent},
+        test_support::{run_ui, ManualClock},
+    };
+
+    fn counting_commit(commits: &Arc<AtomicUsize>) -> impl FnOnce() + Send + 'static {
+        let commits = commits.clone();
+        move || {
+            commits.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn commits_once_the_time_is_up() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let commits = Arc::new(AtomicUsize::new(0));
+
+        states.deferred_action("delete", Duration::seconds(5), counting_commit(&commits));
+        clock.advance(Duration::seconds(4));
+        assert!(states.is_deferred("delete"));
+        assert_eq!(commits.load(Ordering::SeqCst), 0);
+
+        clock.advance(Duration::seconds(1));
+        assert_eq!(
+            states.drain_events(),
+            vec![StateEvent::DeferredActionFinished {
+                name: "delete".to_string(),
+                outcome: DeferredOutcome::Committed,
+            }]
+        );
+        assert_eq!(commits.load(Ordering::SeqCst), 1);
+        assert!(!states.is_deferred("delete"));
+        assert_eq!(
+            states.deferred_outcome("delete"),
+            Some(DeferredOutcome::Committed)
+        );
+        assert_eq!(states.deferred_outcome("delete"), None);
+    }
+
+    #[test]
+    fn undo_drops_the_action() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+        let commits = Arc::new(AtomicUsize::new(0));
+
+        states.deferred_action("delete", Duration::seconds(5), counting_commit(&commits));
+        let mut outcome = None;
+        run_ui(&ctx, |ui| {
+            outcome = states
+                .deferred_action_ui("delete")
+                .pending_ui(|_, _, undo| undo())
+                .show(ui);
+        });
+        assert_eq!(outcome, Some(DeferredOutcome::Undone));
+
+        clock.advance(Duration::seconds(10));
+        states.poll();
+        assert_eq!(commits.load(Ordering::SeqCst), 0);
+        assert!(!states.is_deferred("delete"));
+    }
+
+    #[test]
+    fn staging_again_commits_the_previous_action() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let commits = Arc::new(AtomicUsize::new(0));
+
+        states.deferred_action("delete", Duration::seconds(5), counting_commit(&commits));
+        states.deferred_action("delete", Duration::seconds(5), counting_commit(&commits));
+        assert_eq!(commits.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            states.deferred_outcome("delete"),
+            Some(DeferredOutcome::Committed)
+        );
+        assert!(states.is_deferred("delete"));
+    }
+}
@@ -0,0 +1,142 @@
+//! Default widget used to display a [`BoxedSendError`].
+//!
+//! Used by [`FutureStatusBuilder::default`][crate::future_await::FutureStatusBuilder::default]
+//! and the [`DefaultPromiseAwaitBuilder`][crate::default_promise_await::DefaultPromiseAwaitBuilder],
+//! but can also be used on its own inside of a custom `done_ui`.
+//!
+//! ```
+//! self.ui
+//!     .future_status::<T>("future_name")
+//!     .done_ui(|ui, result, reset| match result {
+//!         Ok(value) => { ui.label(value.to_string()); },
+//!         Err(err) => { ErrorUi::new(err).show(ui); },
+//!     })
+//!     .show(ui);
+//! ```
+//!
+//! Fonts and all colors not set in the [`LightStatesStyle`] are taken from the
+//! [`egui::Style`] of the [`Ui`] the widget is shown in, so it follows the
+//! theme of the application.
+
+use std::error::Error;
+
+use egui::{CollapsingHeader, RichText, TextStyle, Ui};
+use lazy_async_promise::BoxedSendError;
+
+use crate::{
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+};
+
+pub struct ErrorUi<'error> {
+    error: &'error BoxedSendError,
+    retryable: bool,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+}
+
+pub struct ErrorUiResponse {
+    pub retry_clicked: bool,
+}
+
+impl<'error> ErrorUi<'error> {
+    pub fn new(error: &'error BoxedSendError) -> Self {
+        Self {
+            error,
+            retryable: false,
+            strings: LightStatesStrings::default(),
+            style: LightStatesStyle::default(),
+        }
+    }
+    /// Texts used for the buttons and sections, defaults to
+    /// [`LightStatesStrings::default`].
+    #[must_use]
+    pub fn strings(mut self, strings: LightStatesStrings) -> Self {
+        self.strings = strings;
+        self
+    }
+    /// Style used for the message and buttons, defaults to
+    /// [`LightStatesStyle::default`].
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Shows a retry button, check [`ErrorUiResponse::retry_clicked`] after
+    /// showing the widget.
+    #[must_use]
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+    pub fn show(self, ui: &mut Ui) -> ErrorUiResponse {
+        let Self {
+            error,
+            retryable,
+            strings,
+            style,
+        } = self;
+        let mut retry_clicked = false;
+
+        ui.vertical(|ui| {
+            style.with_buttons(
+                ui,
+                |ui| {
+                    ui.label(style.error_text(ui, error.0.to_string()));
+                },
+                |ui| {
+                    if ui.small_button(strings.get(TextKey::Copy)).clicked() {
+                        ui.ctx()
+                            .copy_text(error_report(error, &strings.get(TextKey::CausedBy)));
+                    }
+                    if retryable && ui.small_button(strings.get(TextKey::Retry)).clicked() {
+                        retry_clicked = true;
+                    }
+                },
+            );
+
+            let sources = error_sources(error);
+            if !sources.is_empty() {
+                CollapsingHeader::new(strings.get(TextKey::CausedBy))
+                    .id_salt(ui.next_auto_id())
+                    .show(ui, |ui| {
+                        for source in sources {
+                            ui.label(source);
+                        }
+                    });
+            }
+
+            CollapsingHeader::new(strings.get(TextKey::Details))
+                .id_salt(ui.next_auto_id())
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(format!("{:#?}", error.0)).text_style(TextStyle::Monospace),
+                    );
+                });
+        });
+
+        ErrorUiResponse { retry_clicked }
+    }
+}
+
+fn error_sources(error: &BoxedSendError) -> Vec<String> {
+    let mut sources = vec![];
+    let mut source = error.0.source();
+    while let Some(err) = source {
+        sources.push(err.to_string());
+        source = err.source();
+    }
+    sources
+}
+
+/// Message, source chain and debug output of the error, as copied to the
+/// clipboard.
+fn error_report(error: &BoxedSendError, caused_by: &str) -> String {
+    let mut report = error.0.to_string();
+    for source in error_sources(error) {
+        report.push_str(&format!("\n{caused_by}: "));
+        report.push_str(&source);
+    }
+    report.push_str(&format!("\n\n{:#?}", error.0));
+    report
+}
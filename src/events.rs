@@ -0,0 +1,42 @@
+//! Events produced by the states in [`UiStates`][crate::UiStates].
+//!
+//! Events are queued independently of any widget being shown and can be
+//! collected once per frame through [`drain_events`][crate::UiStates::drain_events].
+
+/// Something that happened to one of the states stored in
+/// [`UiStates`][crate::UiStates].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateEvent {
+    /// A future set through [`set_future`][crate::future_await::FutureAwait::set_future]
+    /// stopped running.
+    FutureCompleted {
+        name: String,
+        outcome: FutureOutcome,
+    },
+    /// A timer set through [`timer_at`][crate::timer::CreateTimerUi::timer_at]
+    /// reached its deadline.
+    TimerExpired { name: String },
+    /// An action staged through [`deferred_action`][crate::deferred::DeferredActions::deferred_action]
+    /// was either committed or undone.
+    DeferredActionFinished {
+        name: String,
+        outcome: DeferredOutcome,
+    },
+}
+
+/// How a future finished.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FutureOutcome {
+    Success,
+    /// Contains the formatted error.
+    Error(String),
+    /// The future finished without producing a value.
+    Empty,
+}
+
+/// What happened to a deferred action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeferredOutcome {
+    Committed,
+    Undone,
+}
@@ -0,0 +1,108 @@
+//! Short lived feedback like "Copied!" or "Saved!" that is shown for a while
+//! after something happened and then fades out.
+//!
+//! ```
+//! if ui.button("copy").clicked() {
+//!     ui.ctx().copy_text(self.text.clone());
+//!     self.ui.flash("copied");
+//! }
+//! self.ui.flash_ui(ui, "copied", Duration::seconds(2), |ui| {
+//!     ui.label("Copied!");
+//! });
+//! ```
+//!
+//! [`flash`][Flash::flash] can be called from anywhere that has access to the
+//! [`UiStates`], the message is shown wherever [`flash_ui`][Flash::flash_ui]
+//! is called for the same name.
+
+use chrono::Duration;
+use egui::Ui;
+
+use crate::{timer::TimerState, UiStates};
+
+/// Longest time spent fading out at the end of a flash.
+const MAX_FADE_OUT_MILLIS: i64 = 500;
+
+pub trait Flash {
+    /// Starts showing the flash, restarting it if it is already shown.
+    fn flash(&mut self, name: impl Into<String>);
+
+    /// Only knows the duration of the flash once [`flash_ui`][Flash::flash_ui]
+    /// was called for it, until then a started flash counts as flashing.
+    fn is_flashing(&mut self, name: impl Into<String>) -> bool;
+
+    /// Shows the contents while the flash is active, fading them out towards
+    /// the end. Returns what the contents returned if they were shown.
+    fn flash_ui<R>(
+        &mut self,
+        ui: &mut Ui,
+        name: impl Into<String>,
+        duration: Duration,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<R>;
+}
+
+impl Flash for UiStates {
+    fn flash(&mut self, name: impl Into<String>) {
+        let now = self.clock.now();
+        self.get_mut(name.into(), FlashState::default())
+            .timer
+            .start(now);
+    }
+
+    fn is_flashing(&mut self, name: impl Into<String>) -> bool {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), FlashState::default());
+        if state.duration_known {
+            state.timer.finish_if_elapsed(now);
+        }
+        state.timer.is_running()
+    }
+
+    fn flash_ui<R>(
+        &mut self,
+        ui: &mut Ui,
+        name: impl Into<String>,
+        duration: Duration,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<R> {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), FlashState::default());
+        state.duration_known = true;
+        let timer = &mut state.timer;
+        timer.set_duration(duration);
+        timer.finish_if_elapsed(now);
+        let remaining = timer.remaining(now)?;
+
+        let fade_out = (duration.num_milliseconds() / 2).min(MAX_FADE_OUT_MILLIS);
+        let opacity = if fade_out > 0 {
+            (remaining.num_milliseconds() as f32 / fade_out as f32).min(1.)
+        } else {
+            1.
+        };
+        ui.ctx().request_repaint();
+        Some(
+            ui.scope(|ui| {
+                ui.multiply_opacity(opacity);
+                add_contents(ui)
+            })
+            .inner,
+        )
+    }
+}
+
+struct FlashState {
+    timer: TimerState,
+    /// Set once [`Flash::flash_ui`] gave the timer its duration, the flash
+    /// does not expire before that.
+    duration_known: bool,
+}
+
+impl Default for FlashState {
+    fn default() -> Self {
+        Self {
+            timer: TimerState::from(Duration::zero()),
+            duration_known: false,
+        }
+    }
+}
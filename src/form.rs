@@ -0,0 +1,505 @@
+//! Editable copy of a model with validation, dirty and touched tracking and a
+//! submission that is shown like a [`future_status`][crate::future_await::FutureAwait::future_status].
+//!
+//! ```
+//! let client = self.client.clone();
+//! let submitted = self
+//!     .ui
+//!     .form::<Profile>("profile", self.profile.clone())
+//!     .validator("name", |profile| {
+//!         if profile.name.is_empty() {
+//!             Err("name is required".to_string())
+//!         } else {
+//!             Ok(())
+//!         }
+//!     })
+//!     .async_validator("email", move |profile| {
+//!         ImmediateValuePromise::new(client.check_email(profile.email.clone()))
+//!     })
+//!     .on_submit(move |profile| ImmediateValuePromise::new(client.save(profile.clone())))
+//!     .default()
+//!     .form_ui(|ui, form| {
+//!         form.field(ui, "name", |ui, profile| ui.text_edit_singleline(&mut profile.name));
+//!         form.field(ui, "email", |ui, profile| ui.text_edit_singleline(&mut profile.email));
+//!     })
+//!     .show(ui);
+//! if let Some(profile) = submitted {
+//!     self.profile = profile;
+//! }
+//! ```
+//!
+//! Errors of a field are only shown once it was touched or a submit was
+//! attempted. Async validators only run for fields that were edited, once
+//! the value stopped changing for the
+//! [`validation_delay`][FormBuilder::validation_delay].
+
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+};
+
+use chrono::{DateTime, Duration, Local};
+use egui::{Button, Response, Ui};
+use lazy_async_promise::{ImmediateValuePromise, ImmediateValueState};
+
+use crate::{
+    clock::SharedClock,
+    events::StateEvent,
+    future_await::{FutureState, FutureStatusBuilder},
+    rate_limit::DebounceState,
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    UiStates,
+};
+
+const DEFAULT_VALIDATION_DELAY_MILLIS: i64 = 300;
+
+/// Bounds for the models edited through a [`FormBuilder`].
+pub trait FormValueTraits
+where
+    Self: Clone + PartialEq + Send + 'static,
+{
+}
+
+impl<T> FormValueTraits for T where T: Clone + PartialEq + Send + 'static {}
+
+pub trait CreateForm {
+    /// If `initial` differs from the one of the previous call and the form
+    /// has no unsaved edits or pending submission, the form is reset to it.
+    #[must_use]
+    fn form<T>(&mut self, name: impl Into<String>, initial: T) -> FormBuilder<T>
+    where
+        T: FormValueTraits;
+}
+
+impl CreateForm for UiStates {
+    fn form<T>(&mut self, name: impl Into<String>, initial: T) -> FormBuilder<T>
+    where
+        T: FormValueTraits,
+    {
+        let name = name.into();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let now = self.clock.now();
+        self.register_poller(name.clone(), poll_form_state::<T>);
+        let (state, events) =
+            self.get_mut_with_events(name.clone(), FormState::new(initial.clone()));
+        if state.external_initial != initial
+            && !state.is_dirty()
+            && !state.submission.is_running()
+            && state.submitted.is_none()
+        {
+            *state = FormState::new(initial);
+        }
+        FormBuilder {
+            name,
+            form: Form {
+                state,
+                style,
+                now,
+                validation_delay: Duration::milliseconds(DEFAULT_VALIDATION_DELAY_MILLIS),
+                validators: vec![],
+                async_validators: vec![],
+                on_submit: None,
+            },
+            events,
+            strings,
+            form_ui: None,
+            buttons_ui: None,
+        }
+    }
+}
+
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+type AsyncValidator<T> = Box<dyn Fn(&T) -> ImmediateValuePromise<Option<String>>>;
+type OnSubmit<T> = Box<dyn FnMut(&T) -> ImmediateValuePromise<()>>;
+type FormUi<T> = Box<dyn FnOnce(&mut Ui, &mut Form<T>)>;
+
+pub struct FormBuilder<'state, T>
+where
+    T: FormValueTraits,
+{
+    name: String,
+    form: Form<'state, T>,
+    events: &'state mut Vec<StateEvent>,
+    strings: LightStatesStrings,
+    form_ui: Option<FormUi<T>>,
+    buttons_ui: Option<FormUi<T>>,
+}
+
+impl<'state, T> FormBuilder<'state, T>
+where
+    T: FormValueTraits,
+{
+    /// Submit and reset buttons below the form, which are enabled while the
+    /// form has unsaved edits.
+    #[must_use]
+    pub fn default(self) -> Self {
+        let strings = self.strings.clone();
+        self.buttons_ui(move |ui, form| {
+            ui.horizontal(|ui| {
+                let enabled = form.is_dirty() && !form.is_submitting();
+                if ui
+                    .add_enabled(enabled, Button::new(strings.get(TextKey::Submit)))
+                    .clicked()
+                {
+                    form.submit();
+                }
+                if ui
+                    .add_enabled(enabled, Button::new(strings.get(TextKey::Reset)))
+                    .clicked()
+                {
+                    form.reset();
+                }
+            });
+        })
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.form.style = style;
+        self
+    }
+    /// Checks the field of that name, returning the error message if the
+    /// value is invalid. A field can have multiple validators, the first
+    /// error is shown.
+    #[must_use]
+    pub fn validator(
+        mut self,
+        field: impl Into<String>,
+        validator: impl Fn(&T) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.form
+            .validators
+            .push((field.into(), Box::new(validator)));
+        self
+    }
+    /// Checks the field of that name in the background after it was edited.
+    /// The future resolves to the error message, or `None` if the value is
+    /// valid.
+    #[must_use]
+    pub fn async_validator(
+        mut self,
+        field: impl Into<String>,
+        validator: impl Fn(&T) -> ImmediateValuePromise<Option<String>> + 'static,
+    ) -> Self {
+        self.form
+            .async_validators
+            .push((field.into(), Box::new(validator)));
+        self
+    }
+    /// How long a field has to stay unchanged before its async validators
+    /// run, defaults to 300 milliseconds.
+    #[must_use]
+    pub fn validation_delay(mut self, delay: Duration) -> Self {
+        self.form.validation_delay = delay;
+        self
+    }
+    /// Creates the future saving the value on [`Form::submit`]. Without it
+    /// submitting succeeds right away.
+    #[must_use]
+    pub fn on_submit(
+        mut self,
+        on_submit: impl FnMut(&T) -> ImmediateValuePromise<()> + 'static,
+    ) -> Self {
+        self.form.on_submit = Some(Box::new(on_submit));
+        self
+    }
+    /// Fields of the form, added through [`Form::field`].
+    #[must_use]
+    pub fn form_ui(mut self, form_ui: impl FnOnce(&mut Ui, &mut Form<T>) + 'static) -> Self {
+        self.form_ui = Some(Box::new(form_ui));
+        self
+    }
+    /// Shown below the fields and the status of the submission.
+    #[must_use]
+    pub fn buttons_ui(mut self, buttons_ui: impl FnOnce(&mut Ui, &mut Form<T>) + 'static) -> Self {
+        self.buttons_ui = Some(Box::new(buttons_ui));
+        self
+    }
+    /// Returns the submitted value once, on the call after its submission
+    /// succeeded.
+    pub fn show(self, ui: &mut Ui) -> Option<T> {
+        let Self {
+            name,
+            mut form,
+            events,
+            strings,
+            form_ui,
+            buttons_ui,
+        } = self;
+        form.state.poll(&name, events);
+        if let Some(form_ui) = form_ui {
+            form_ui(ui, &mut form);
+        }
+        FutureStatusBuilder::new(
+            name,
+            &mut form.state.submission,
+            events,
+            strings,
+            form.style.clone(),
+        )
+        .default()
+        .show(ui);
+        if let Some(buttons_ui) = buttons_ui {
+            buttons_ui(ui, &mut form);
+        }
+        // pending validations schedule their own repaint
+        if !form.state.validating.is_empty() {
+            ui.ctx().request_repaint();
+        }
+        form.state.submitted.take()
+    }
+}
+
+/// Value and validation of a form, handed to its UIs.
+pub struct Form<'state, T>
+where
+    T: FormValueTraits,
+{
+    state: &'state mut FormState<T>,
+    style: LightStatesStyle,
+    now: DateTime<Local>,
+    validation_delay: Duration,
+    validators: Vec<(String, Validator<T>)>,
+    async_validators: Vec<(String, AsyncValidator<T>)>,
+    on_submit: Option<OnSubmit<T>>,
+}
+
+impl<'state, T> Form<'state, T>
+where
+    T: FormValueTraits,
+{
+    pub fn value(&self) -> &T {
+        &self.state.value
+    }
+    /// Edits made through this are not tracked per field, use
+    /// [`field`][Form::field] for that.
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.state.value
+    }
+    /// Adds the UI of a field, tracking edits and focus through the returned
+    /// response. Shows a spinner while the field is validated and its error
+    /// once it was touched.
+    pub fn field(
+        &mut self,
+        ui: &mut Ui,
+        field: impl Into<String>,
+        add_contents: impl FnOnce(&mut Ui, &mut T) -> Response,
+    ) -> Response {
+        let field = field.into();
+        let response = add_contents(ui, &mut self.state.value);
+        if response.changed() {
+            self.state.dirty.insert(field.clone());
+            self.state.touched.insert(field.clone());
+            if !self.state.submission.is_running() {
+                self.state.submission.clear();
+            }
+            self.queue_validation(&field);
+        }
+        self.validate_settled(ui, &field);
+        if response.lost_focus() {
+            self.state.touched.insert(field.clone());
+        }
+
+        if self.state.validating.contains_key(&field) {
+            ui.add(self.style.spinner());
+        } else if self.state.submit_attempted || self.state.touched.contains(&field) {
+            if let Some(error) = self.field_error(&field) {
+                ui.label(self.style.error_text(ui, error));
+            }
+        }
+        response
+    }
+    /// `true` if the value differs from the last submitted or initial one.
+    pub fn is_dirty(&self) -> bool {
+        self.state.is_dirty()
+    }
+    /// `true` if the field was edited since the last submit or reset.
+    pub fn is_field_dirty(&self, field: &str) -> bool {
+        self.state.dirty.contains(field)
+    }
+    /// `true` if the field was edited or lost focus since the last submit or
+    /// reset.
+    pub fn is_touched(&self, field: &str) -> bool {
+        self.state.touched.contains(field)
+    }
+    /// `true` while an async validation runs or waits for its field to stop
+    /// changing.
+    pub fn is_validating(&self) -> bool {
+        !self.state.validating.is_empty() || !self.state.pending_validations.is_empty()
+    }
+    pub fn is_submitting(&self) -> bool {
+        self.state.submission.is_running()
+    }
+    /// First error of the sync validators of the field, otherwise the result
+    /// of its last async validation.
+    pub fn field_error(&self, field: &str) -> Option<String> {
+        self.validators
+            .iter()
+            .filter(|(name, _)| name == field)
+            .find_map(|(_, validator)| validator(&self.state.value).err())
+            .or_else(|| self.state.async_errors.get(field).cloned())
+    }
+    /// `true` if no validator reports an error and no async validation is
+    /// running.
+    pub fn is_valid(&self) -> bool {
+        self.state.async_errors.is_empty()
+            && !self.is_validating()
+            && self
+                .validators
+                .iter()
+                .all(|(_, validator)| validator(&self.state.value).is_ok())
+    }
+    /// Shows the errors of all fields and, if the form is valid, submits it
+    /// through the [`on_submit`][FormBuilder::on_submit] function.
+    pub fn submit(&mut self) {
+        self.state.submit_attempted = true;
+        if !self.is_valid() || self.is_submitting() {
+            return;
+        }
+        match self.on_submit.as_mut() {
+            Some(on_submit) => {
+                let promise = on_submit(&self.state.value);
+                self.state.submission.start(promise);
+                self.state.submitting = true;
+            }
+            None => self.state.commit(),
+        }
+    }
+    /// Discards all edits, going back to the last submitted or initial value.
+    pub fn reset(&mut self) {
+        let initial = self.state.initial.clone();
+        let external_initial = self.state.external_initial.clone();
+        *self.state = FormState {
+            external_initial,
+            ..FormState::new(initial)
+        };
+    }
+    /// Waits for the edited field to stop changing before validating it.
+    fn queue_validation(&mut self, field: &str) {
+        if !self.async_validators.iter().any(|(name, _)| name == field) {
+            return;
+        }
+        self.state.async_errors.remove(field);
+        let delay = self.validation_delay;
+        self.state
+            .pending_validations
+            .entry(field.to_string())
+            .or_insert_with(|| DebounceState::from(delay));
+    }
+    /// Starts the validation of the field once its value settled.
+    fn validate_settled(&mut self, ui: &Ui, field: &str) {
+        let Some(pending) = self.state.pending_validations.get_mut(field) else {
+            return;
+        };
+        let Some(value) = pending.update(ui.ctx(), self.now, self.state.value.clone()) else {
+            return;
+        };
+        self.state.pending_validations.remove(field);
+        if let Some((_, validator)) = self.async_validators.iter().find(|(name, _)| name == field) {
+            self.state
+                .validating
+                .insert(field.to_string(), validator(&value));
+        }
+    }
+}
+
+struct FormState<T>
+where
+    T: FormValueTraits,
+{
+    /// Value the edits are compared against, replaced on every successful
+    /// submit.
+    initial: T,
+    /// Last value given to [`CreateForm::form`], which can differ from
+    /// `initial` after a submit until the caller stores the submitted value.
+    external_initial: T,
+    value: T,
+    dirty: HashSet<String>,
+    touched: HashSet<String>,
+    submit_attempted: bool,
+    /// Edited fields waiting for the value to settle before validating.
+    pending_validations: HashMap<String, DebounceState<T>>,
+    /// Running async validations by field.
+    validating: HashMap<String, ImmediateValuePromise<Option<String>>>,
+    async_errors: HashMap<String, String>,
+    submission: FutureState<()>,
+    /// Set from submitting until the submission was seen finishing, the
+    /// status UI polls the submission as well.
+    submitting: bool,
+    /// Kept until it is returned by [`FormBuilder::show`].
+    submitted: Option<T>,
+}
+
+impl<T> FormState<T>
+where
+    T: FormValueTraits,
+{
+    fn new(initial: T) -> Self {
+        Self {
+            value: initial.clone(),
+            external_initial: initial.clone(),
+            initial,
+            dirty: HashSet::new(),
+            touched: HashSet::new(),
+            submit_attempted: false,
+            pending_validations: HashMap::new(),
+            validating: HashMap::new(),
+            async_errors: HashMap::new(),
+            submission: FutureState::default(),
+            submitting: false,
+            submitted: None,
+        }
+    }
+    fn is_dirty(&self) -> bool {
+        self.value != self.initial
+    }
+    /// Makes the current value the new initial one.
+    fn commit(&mut self) {
+        self.initial = self.value.clone();
+        self.dirty.clear();
+        self.touched.clear();
+        self.submit_attempted = false;
+        self.submitted = Some(self.value.clone());
+    }
+    /// Collects finished async validations and commits the value once its
+    /// submission succeeded.
+    fn poll(&mut self, name: &str, events: &mut Vec<StateEvent>) {
+        let async_errors = &mut self.async_errors;
+        self.validating
+            .retain(|field, promise| match promise.poll_state() {
+                ImmediateValueState::Updating => true,
+                ImmediateValueState::Success(Some(error)) => {
+                    async_errors.insert(field.clone(), error.clone());
+                    false
+                }
+                ImmediateValueState::Error(err) => {
+                    async_errors.insert(field.clone(), err.0.to_string());
+                    false
+                }
+                _ => false,
+            });
+
+        self.submission.poll(name, events);
+        if self.submitting && !self.submission.is_running() {
+            self.submitting = false;
+            if self.submission.succeeded() {
+                self.commit();
+            }
+        }
+    }
+}
+
+fn poll_form_state<T>(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    _: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) where
+    T: FormValueTraits,
+{
+    if let Some(state) = state.downcast_mut::<FormState<T>>() {
+        state.poll(name, events);
+    }
+}
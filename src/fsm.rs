@@ -0,0 +1,417 @@
+//! Finite state machines with a UI per state, for widgets that go through a
+//! fixed set of steps.
+//!
+//! The transitions are declared on every call, like the rest of the UI, only
+//! the current state and the queued events are stored.
+//!
+//! ```
+//! #[derive(Clone, PartialEq, Default)]
+//! enum Upload {
+//!     #[default]
+//!     Idle,
+//!     Confirming,
+//!     Uploading,
+//!     Done,
+//! }
+//!
+//! #[derive(PartialEq)]
+//! enum UploadEvent {
+//!     Start,
+//!     Confirm,
+//!     Cancel,
+//!     Finished,
+//! }
+//!
+//! let has_file = self.file.is_some();
+//! self.ui
+//!     .fsm::<Upload, UploadEvent>("upload")
+//!     .transition(Upload::Idle, UploadEvent::Start, Upload::Confirming)
+//!     .transition(Upload::Confirming, UploadEvent::Cancel, Upload::Idle)
+//!     .transition_if(
+//!         Upload::Confirming,
+//!         UploadEvent::Confirm,
+//!         Upload::Uploading,
+//!         move |_| has_file,
+//!     )
+//!     .transition(Upload::Uploading, UploadEvent::Finished, Upload::Done)
+//!     .timed_transition(Upload::Done, Duration::seconds(3), Upload::Idle)
+//!     .state_ui(Upload::Idle, |ui, send| {
+//!         if ui.button("upload").clicked() {
+//!             send(UploadEvent::Start);
+//!         }
+//!     })
+//!     .state_ui(Upload::Done, |ui, _| {
+//!         ui.label("uploaded");
+//!     })
+//!     .show(ui);
+//!
+//! // events can be sent from anywhere, they are handled on the next `show`
+//! self.ui
+//!     .send_event::<Upload, _>("upload", UploadEvent::Finished);
+//! ```
+
+use chrono::{DateTime, Duration, Local};
+use egui::Ui;
+
+use crate::{timer::TimerState, UiStates};
+
+/// Bounds for the states of a [`FsmBuilder`], the machine starts in the
+/// default state.
+pub trait FsmStateTraits
+where
+    Self: Clone + PartialEq + Default + Send + 'static,
+{
+}
+
+impl<T> FsmStateTraits for T where T: Clone + PartialEq + Default + Send + 'static {}
+
+/// Bounds for the events of a [`FsmBuilder`].
+pub trait FsmEventTraits
+where
+    Self: PartialEq + Send + 'static,
+{
+}
+
+impl<T> FsmEventTraits for T where T: PartialEq + Send + 'static {}
+
+pub trait CreateFsm {
+    #[must_use]
+    fn fsm<S, E>(&mut self, name: impl Into<String>) -> FsmBuilder<S, E>
+    where
+        S: FsmStateTraits,
+        E: FsmEventTraits;
+
+    /// Queues an event for the state machine of that name. It is handled on
+    /// the next call to [`FsmBuilder::show`].
+    fn send_event<S, E>(&mut self, name: impl Into<String>, event: E)
+    where
+        S: FsmStateTraits,
+        E: FsmEventTraits;
+}
+
+impl CreateFsm for UiStates {
+    fn fsm<S, E>(&mut self, name: impl Into<String>) -> FsmBuilder<S, E>
+    where
+        S: FsmStateTraits,
+        E: FsmEventTraits,
+    {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), FsmState::new(now));
+        FsmBuilder {
+            state,
+            now,
+            transitions: vec![],
+            timed_transitions: vec![],
+            on_enter: vec![],
+            on_exit: vec![],
+            state_uis: vec![],
+        }
+    }
+
+    fn send_event<S, E>(&mut self, name: impl Into<String>, event: E)
+    where
+        S: FsmStateTraits,
+        E: FsmEventTraits,
+    {
+        let now = self.clock.now();
+        self.get_mut::<FsmState<S, E>>(name.into(), FsmState::new(now))
+            .pending
+            .push(event);
+    }
+}
+
+type Guard<E> = Box<dyn Fn(&E) -> bool>;
+type Action = Box<dyn FnMut()>;
+type StateUi<E> = Box<dyn FnOnce(&mut Ui, &mut dyn FnMut(E))>;
+
+struct Transition<S, E> {
+    from: S,
+    event: E,
+    to: S,
+    guard: Option<Guard<E>>,
+}
+
+impl<S, E> Transition<S, E>
+where
+    S: FsmStateTraits,
+    E: FsmEventTraits,
+{
+    fn allows(&self, current: &S, event: &E) -> bool {
+        if self.from != *current || self.event != *event {
+            return false;
+        }
+        match &self.guard {
+            Some(guard) => guard(event),
+            None => true,
+        }
+    }
+}
+
+pub struct FsmBuilder<'state, S, E>
+where
+    S: FsmStateTraits,
+    E: FsmEventTraits,
+{
+    state: &'state mut FsmState<S, E>,
+    now: DateTime<Local>,
+    transitions: Vec<Transition<S, E>>,
+    timed_transitions: Vec<(S, Duration, S)>,
+    on_enter: Vec<(S, Action)>,
+    on_exit: Vec<(S, Action)>,
+    state_uis: Vec<(S, StateUi<E>)>,
+}
+
+impl<'state, S, E> FsmBuilder<'state, S, E>
+where
+    S: FsmStateTraits,
+    E: FsmEventTraits,
+{
+    /// Moves from `from` to `to` when `event` is received. Events without a
+    /// matching transition are dropped.
+    #[must_use]
+    pub fn transition(mut self, from: S, event: E, to: S) -> Self {
+        self.transitions.push(Transition {
+            from,
+            event,
+            to,
+            guard: None,
+        });
+        self
+    }
+    /// Same as [`transition`][FsmBuilder::transition] but only taken if the
+    /// guard returns `true` for the received event.
+    #[must_use]
+    pub fn transition_if(
+        mut self,
+        from: S,
+        event: E,
+        to: S,
+        guard: impl Fn(&E) -> bool + 'static,
+    ) -> Self {
+        self.transitions.push(Transition {
+            from,
+            event,
+            to,
+            guard: Some(Box::new(guard)),
+        });
+        self
+    }
+    /// Moves from `from` to `to` once the machine has been in `from` for
+    /// `after`.
+    #[must_use]
+    pub fn timed_transition(mut self, from: S, after: Duration, to: S) -> Self {
+        self.timed_transitions.push((from, after, to));
+        self
+    }
+    /// Runs every time the state is entered, including transitions from the
+    /// state to itself. Not run for the initial state.
+    #[must_use]
+    pub fn on_enter(mut self, state: S, on_enter: impl FnMut() + 'static) -> Self {
+        self.on_enter.push((state, Box::new(on_enter)));
+        self
+    }
+    /// Runs every time the state is left, before the actions of the next
+    /// state.
+    #[must_use]
+    pub fn on_exit(mut self, state: S, on_exit: impl FnMut() + 'static) -> Self {
+        self.on_exit.push((state, Box::new(on_exit)));
+        self
+    }
+    /// Shown while the machine is in that state. Gets a function to send
+    /// events, which are handled right after the UI.
+    #[must_use]
+    pub fn state_ui(
+        mut self,
+        state: S,
+        state_ui: impl FnOnce(&mut Ui, &mut dyn FnMut(E)) + 'static,
+    ) -> Self {
+        self.state_uis.push((state, Box::new(state_ui)));
+        self
+    }
+    /// State the machine is in before this call handles any events.
+    pub fn current(&self) -> &S {
+        &self.state.current
+    }
+    /// Handles the queued events and timed transitions, then shows the UI of
+    /// the current state. Returns the state that was entered last, if any
+    /// transition was taken during this call.
+    pub fn show(mut self, ui: &mut Ui) -> Option<S> {
+        let mut entered = None;
+        for event in std::mem::take(&mut self.state.pending) {
+            entered = self.handle(&event).or(entered);
+        }
+        entered = self.handle_timed(ui).or(entered);
+
+        let mut sent = vec![];
+        if let Some(index) = self
+            .state_uis
+            .iter()
+            .position(|(state, _)| *state == self.state.current)
+        {
+            let (_, state_ui) = self.state_uis.swap_remove(index);
+            state_ui(ui, &mut |event| sent.push(event));
+        }
+        for event in sent {
+            entered = self.handle(&event).or(entered);
+        }
+
+        if entered.is_some() {
+            ui.ctx().request_repaint();
+        }
+        entered
+    }
+    fn handle(&mut self, event: &E) -> Option<S> {
+        let to = self
+            .transitions
+            .iter()
+            .find(|transition| transition.allows(&self.state.current, event))?
+            .to
+            .clone();
+        self.enter(to.clone());
+        Some(to)
+    }
+    fn handle_timed(&mut self, ui: &Ui) -> Option<S> {
+        let (after, to) = self
+            .timed_transitions
+            .iter()
+            .find(|(from, ..)| *from == self.state.current)
+            .map(|(_, after, to)| (*after, to.clone()))?;
+        self.state.timer.set_duration(after);
+        if self.state.timer.finish_if_elapsed(self.now) {
+            self.enter(to.clone());
+            return Some(to);
+        }
+        if let Some(remaining) = self.state.timer.remaining(self.now) {
+            ui.ctx()
+                .request_repaint_after(remaining.to_std().unwrap_or_default());
+        }
+        None
+    }
+    fn enter(&mut self, to: S) {
+        let from = std::mem::replace(&mut self.state.current, to.clone());
+        run_actions(&mut self.on_exit, &from);
+        run_actions(&mut self.on_enter, &to);
+        self.state.timer.start(self.now);
+    }
+}
+
+fn run_actions<S>(actions: &mut [(S, Action)], state: &S)
+where
+    S: FsmStateTraits,
+{
+    for (_, action) in actions.iter_mut().filter(|(s, _)| s == state) {
+        action();
+    }
+}
+
+struct FsmState<S, E> {
+    current: S,
+    /// Events sent through [`CreateFsm::send_event`] that were not yet
+    /// handled.
+    pending: Vec<E>,
+    /// Started whenever a state is entered, for the timed transitions.
+    timer: TimerState,
+}
+
+impl<S, E> FsmState<S, E>
+where
+    S: FsmStateTraits,
+    E: FsmEventTraits,
+{
+    fn new(now: DateTime<Local>) -> Self {
+        let mut timer = TimerState::from(Duration::zero());
+        timer.start(now);
+        Self {
+            current: S::default(),
+            pending: vec![],
+            timer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use egui::Context;
+
+    use super::CreateFsm;
+    use crate::{
+        test_support::{run_ui, ManualClock},
+        UiStates,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    enum Upload {
+        #[default]
+        Idle,
+        Uploading,
+        Done,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum UploadEvent {
+        Start,
+        Finished,
+    }
+
+    /// Shows the machine for one frame, returns the state entered last.
+    fn show(states: &mut UiStates, ctx: &Context, has_file: bool) -> Option<Upload> {
+        let mut entered = None;
+        run_ui(ctx, |ui| {
+            entered = states
+                .fsm::<Upload, UploadEvent>("upload")
+                .transition_if(
+                    Upload::Idle,
+                    UploadEvent::Start,
+                    Upload::Uploading,
+                    move |_| has_file,
+                )
+                .transition(Upload::Uploading, UploadEvent::Finished, Upload::Done)
+                .timed_transition(Upload::Done, Duration::seconds(3), Upload::Idle)
+                .show(ui);
+        });
+        entered
+    }
+
+    #[test]
+    fn guard_blocks_the_transition() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        states.send_event::<Upload, _>("upload", UploadEvent::Start);
+        assert_eq!(show(&mut states, &ctx, false), None);
+        // the event was dropped, not kept for later
+        assert_eq!(show(&mut states, &ctx, true), None);
+
+        states.send_event::<Upload, _>("upload", UploadEvent::Start);
+        assert_eq!(show(&mut states, &ctx, true), Some(Upload::Uploading));
+    }
+
+    #[test]
+    fn events_without_transition_are_dropped() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        states.send_event::<Upload, _>("upload", UploadEvent::Finished);
+        assert_eq!(show(&mut states, &ctx, true), None);
+        states.send_event::<Upload, _>("upload", UploadEvent::Start);
+        assert_eq!(show(&mut states, &ctx, true), Some(Upload::Uploading));
+    }
+
+    #[test]
+    fn timed_transition_waits_in_the_state() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        states.send_event::<Upload, _>("upload", UploadEvent::Start);
+        states.send_event::<Upload, _>("upload", UploadEvent::Finished);
+        assert_eq!(show(&mut states, &ctx, true), Some(Upload::Done));
+        clock.advance(Duration::seconds(2));
+        assert_eq!(show(&mut states, &ctx, true), None);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(show(&mut states, &ctx, true), Some(Upload::Idle));
+    }
+}
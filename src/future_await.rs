@@ -8,6 +8,11 @@
 //! methods to acceess state of internal future like: [`is_running`][is_run] and
 //! [`future_status`][fut_stat]
 //!
+//! Callbacks registered on the [`SetFutureBuilder`] are run exactly once when
+//! the future stops running. This happens either when the state is polled
+//! through one of the methods above or through [`UiStates::poll`], so they
+//! fire even if the status is not shown.
+//!
 //! ```
 //! if !self.ui.is_running::<T>("future_name")
 //!     && ui.button("Save parsed Data").clicked()
@@ -25,12 +30,21 @@
 //! [set_fut]: FutureAwait::set_future
 //! [fut_stat]: FutureAwait::future_status
 
-use egui::{Spinner, Ui};
+use std::any::Any;
+
+use egui::Ui;
 use lazy_async_promise::{
     BoxedSendError, DirectCacheAccess, ImmediateValuePromise, ImmediateValueState,
 };
 
-use crate::UiStates;
+use crate::{
+    clock::SharedClock,
+    error_ui::ErrorUi,
+    events::{FutureOutcome, StateEvent},
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    UiStates,
+};
 
 /// Stores future in internal state through [`set_future`][FutureAwait::set_future] and then provides
 /// methods to acceess state of internal future.
@@ -84,6 +98,25 @@ pub trait FutureAwait {
     /// self.ui.set_future("state_name").set(future);
     /// ```
     ///
+    /// Callbacks can be attached which are run once the future is done:
+    ///
+    /// ```
+    /// self.ui
+    ///     .set_future("state_name")
+    ///     .on_success(|value| println!("saved {value}"))
+    ///     .on_error(|err| println!("failed with {}", err.0))
+    ///     .set(future);
+    /// ```
+    ///
+    /// When the future is created through a factory instead, the default
+    /// status UI is able to offer a retry button on errors:
+    ///
+    /// ```
+    /// self.ui
+    ///     .set_future("state_name")
+    ///     .set_factory(|| ImmediateValuePromise::new(fetch_data()));
+    /// ```
+    ///
     /// [is_run]: crate::future_await::FutureAwait::is_running
     /// [fut_stat]: crate::future_await::FutureAwait::future_status
     #[must_use]
@@ -121,47 +154,264 @@ impl FutureAwait for UiStates {
     where
         T: Send + 'static,
     {
-        self.get_mut::<Option<ImmediateValuePromise<T>>>(name.into(), None)
-            .as_mut()
-            .map(|promise| matches!(promise.poll_state(), ImmediateValueState::Updating))
-            .unwrap_or(false)
+        let name = name.into();
+        let (state, events) =
+            self.get_mut_with_events::<FutureState<T>>(name.clone(), FutureState::default());
+        state.poll(&name, events);
+        state.running
     }
     #[must_use]
     fn set_future<T>(&mut self, name: impl Into<String>) -> SetFutureBuilder<T>
     where
         T: Send + 'static,
     {
-        let state = self.get_mut(name.into(), None);
-        SetFutureBuilder { state }
+        let name = name.into();
+        self.register_poller(name.clone(), poll_future_state::<T>);
+        let state = self.get_mut(name, FutureState::default());
+        SetFutureBuilder {
+            state,
+            on_complete: None,
+            on_success: None,
+            on_error: None,
+        }
     }
     #[must_use]
     fn future_status<T>(&mut self, name: impl Into<String>) -> FutureStatusBuilder<T>
     where
         T: Send + 'static,
     {
-        let state = self.get_mut(name.into(), None);
-        FutureStatusBuilder {
-            state,
-            waiting_ui: None,
-            empty_ui: None,
-            done_ui: None,
-        }
+        let name = name.into();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let (state, events) = self.get_mut_with_events(name.clone(), FutureState::default());
+        FutureStatusBuilder::new(name, state, events, strings, style)
     }
 }
 
+type OnComplete<T> = Box<dyn FnOnce(Result<&T, &BoxedSendError>) + Send>;
+type OnSuccess<T> = Box<dyn FnOnce(&T) + Send>;
+type OnError = Box<dyn FnOnce(&BoxedSendError) + Send>;
+type Factory<T> = Box<dyn FnMut() -> ImmediateValuePromise<T> + Send>;
+// the built-in UIs get the style when shown, so it can be overridden after
+// choosing them
+type WaitingUiFn = Box<dyn FnOnce(&mut Ui, &LightStatesStyle)>;
+type DoneUiFn<T> =
+    Box<dyn FnOnce(&mut Ui, Result<&T, &BoxedSendError>, &mut dyn FnMut(), &LightStatesStyle)>;
+type ErrorUiFn = Box<
+    dyn FnOnce(
+        &mut Ui,
+        &BoxedSendError,
+        Option<&mut dyn FnMut()>,
+        &mut dyn FnMut(),
+        &LightStatesStyle,
+    ),
+>;
+
 pub struct SetFutureBuilder<'state, T>
 where
     T: Send + 'static,
 {
-    state: &'state mut Option<ImmediateValuePromise<T>>,
+    state: &'state mut FutureState<T>,
+    on_complete: Option<OnComplete<T>>,
+    on_success: Option<OnSuccess<T>>,
+    on_error: Option<OnError>,
 }
 
 impl<'state, T> SetFutureBuilder<'state, T>
 where
     T: Send + 'static,
 {
+    /// Runs once the future is done, no matter if it succeeded or not. Is not
+    /// run if the future finished without a value.
+    #[must_use]
+    pub fn on_complete(
+        mut self,
+        on_complete: impl FnOnce(Result<&T, &BoxedSendError>) + Send + 'static,
+    ) -> Self {
+        self.on_complete = Some(Box::new(on_complete));
+        self
+    }
+    /// Runs once the future is done, if it finished successfully.
+    #[must_use]
+    pub fn on_success(mut self, on_success: impl FnOnce(&T) + Send + 'static) -> Self {
+        self.on_success = Some(Box::new(on_success));
+        self
+    }
+    /// Runs once the future is done, if it finished with an error.
+    #[must_use]
+    pub fn on_error(mut self, on_error: impl FnOnce(&BoxedSendError) + Send + 'static) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+    /// Replaces the current future and its callbacks with the new ones.
     pub fn set(self, future: impl Into<ImmediateValuePromise<T>>) {
-        *self.state = Some(future.into());
+        self.set_state(future.into(), None);
+    }
+    /// Same as [`set`][SetFutureBuilder::set] but creates the future through
+    /// the factory. The factory is kept around so the future can be retried,
+    /// the callbacks however will only run for the first attempt.
+    pub fn set_factory(
+        self,
+        mut factory: impl FnMut() -> ImmediateValuePromise<T> + Send + 'static,
+    ) {
+        let promise = factory();
+        self.set_state(promise, Some(Box::new(factory)));
+    }
+    fn set_state(self, promise: ImmediateValuePromise<T>, factory: Option<Factory<T>>) {
+        let Self {
+            state,
+            on_complete,
+            on_success,
+            on_error,
+        } = self;
+        *state = FutureState {
+            promise: Some(promise),
+            running: true,
+            factory,
+            on_complete,
+            on_success,
+            on_error,
+        };
+    }
+}
+
+/// Internal state stored for every name used with [`FutureAwait`].
+pub(crate) struct FutureState<T>
+where
+    T: Send + 'static,
+{
+    promise: Option<ImmediateValuePromise<T>>,
+    /// Set while the promise has not yet been seen leaving the `Updating`
+    /// state. Makes sure the callbacks and events are only fired once.
+    running: bool,
+    factory: Option<Factory<T>>,
+    on_complete: Option<OnComplete<T>>,
+    on_success: Option<OnSuccess<T>>,
+    on_error: Option<OnError>,
+}
+
+impl<T> Default for FutureState<T>
+where
+    T: Send + 'static,
+{
+    fn default() -> Self {
+        Self {
+            promise: None,
+            running: false,
+            factory: None,
+            on_complete: None,
+            on_success: None,
+            on_error: None,
+        }
+    }
+}
+
+impl<T> FutureState<T>
+where
+    T: Send + 'static,
+{
+    /// Replaces the current future, dropping any callbacks and factory.
+    pub(crate) fn start(&mut self, promise: ImmediateValuePromise<T>) {
+        *self = Self {
+            promise: Some(promise),
+            running: true,
+            ..Self::default()
+        };
+    }
+    pub(crate) fn is_running(&self) -> bool {
+        self.running
+    }
+    /// `true` once the future finished with a value.
+    pub(crate) fn succeeded(&mut self) -> bool {
+        !self.running
+            && self
+                .promise
+                .as_mut()
+                .and_then(|promise| promise.get_result())
+                .is_some_and(|result| result.is_ok())
+    }
+    /// Error of a future that failed.
+    pub(crate) fn error(&mut self) -> Option<&BoxedSendError> {
+        if self.running {
+            return None;
+        }
+        self.promise
+            .as_mut()
+            .and_then(|promise| promise.get_result())
+            .and_then(Result::err)
+    }
+    /// Takes the value out of a future that finished successfully.
+    pub(crate) fn take_value(&mut self) -> Option<T> {
+        if self.running {
+            return None;
+        }
+        self.promise
+            .as_mut()
+            .and_then(|promise| promise.take_value())
+    }
+    /// Polls the promise and, if it just stopped running, fires the callbacks
+    /// and queues a [`StateEvent::FutureCompleted`].
+    pub(crate) fn poll(&mut self, name: &str, events: &mut Vec<StateEvent>) {
+        let Some(promise) = self.promise.as_mut() else {
+            self.running = false;
+            return;
+        };
+        if matches!(promise.poll_state(), ImmediateValueState::Updating) || !self.running {
+            return;
+        }
+        self.running = false;
+
+        let outcome = match promise.get_result() {
+            Some(Ok(value)) => {
+                if let Some(on_complete) = self.on_complete.take() {
+                    on_complete(Ok(value));
+                }
+                if let Some(on_success) = self.on_success.take() {
+                    on_success(value);
+                }
+                FutureOutcome::Success
+            }
+            Some(Err(err)) => {
+                if let Some(on_complete) = self.on_complete.take() {
+                    on_complete(Err(err));
+                }
+                if let Some(on_error) = self.on_error.take() {
+                    on_error(err);
+                }
+                FutureOutcome::Error(err.0.to_string())
+            }
+            None => FutureOutcome::Empty,
+        };
+        self.on_complete = None;
+        self.on_success = None;
+        self.on_error = None;
+        events.push(StateEvent::FutureCompleted {
+            name: name.to_string(),
+            outcome,
+        });
+    }
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+    /// Restarts the future through its factory, does nothing if there is none.
+    fn retry(&mut self) {
+        if let Some(factory) = self.factory.as_mut() {
+            self.promise = Some(factory());
+            self.running = true;
+        }
+    }
+}
+
+fn poll_future_state<T>(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    _: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) where
+    T: Send + 'static,
+{
+    if let Some(state) = state.downcast_mut::<FutureState<T>>() {
+        state.poll(name, events);
     }
 }
 
@@ -169,36 +419,117 @@ pub struct FutureStatusBuilder<'state, T>
 where
     T: Send + 'static,
 {
-    state: &'state mut Option<ImmediateValuePromise<T>>,
-    waiting_ui: Option<Box<dyn FnOnce(&mut Ui)>>,
+    name: String,
+    state: &'state mut FutureState<T>,
+    events: &'state mut Vec<StateEvent>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    waiting_ui: Option<WaitingUiFn>,
     empty_ui: Option<Box<dyn FnOnce(&mut Ui)>>,
-    done_ui: Option<Box<dyn FnOnce(&mut Ui, Result<&T, &BoxedSendError>, &mut dyn FnMut())>>,
+    done_ui: Option<DoneUiFn<T>>,
+    error_ui: Option<ErrorUiFn>,
 }
 
 impl<'state, T> FutureStatusBuilder<'state, T>
 where
     T: Send + 'static,
 {
+    /// Status of a future that is stored as part of another state.
+    pub(crate) fn new(
+        name: String,
+        state: &'state mut FutureState<T>,
+        events: &'state mut Vec<StateEvent>,
+        strings: LightStatesStrings,
+        style: LightStatesStyle,
+    ) -> Self {
+        Self {
+            name,
+            state,
+            events,
+            strings,
+            style,
+            waiting_ui: None,
+            empty_ui: None,
+            done_ui: None,
+            error_ui: None,
+        }
+    }
+    /// Spinner while waiting, and on completion either a success label or an
+    /// [`ErrorUi`] showing the error, which offers a retry if the future was
+    /// set with [`set_factory`][SetFutureBuilder::set_factory].
     #[must_use]
-    pub fn default(self) -> Self {
-        self.spinner()
-            .empty_ui(|_| {})
-            .done_ui(|ui, result, reset| {
-                match result {
-                    Ok(_) => ui.label("success"),
-                    Err(_) => ui.label("error"),
-                };
-                if ui.button("clear").clicked() {
-                    reset();
-                }
-            })
+    pub fn default(mut self) -> Self {
+        let done_strings = self.strings.clone();
+        let error_strings = self.strings.clone();
+        self.done_ui = Some(Box::new(move |ui, result, reset, style| {
+            style.with_buttons(
+                ui,
+                |ui| {
+                    match result {
+                        Ok(_) => {
+                            ui.label(style.success_text(ui, done_strings.get(TextKey::Success)))
+                        }
+                        Err(_) => ui.label(style.error_text(ui, done_strings.get(TextKey::Error))),
+                    };
+                },
+                |ui| {
+                    if ui.button(done_strings.get(TextKey::Clear)).clicked() {
+                        reset();
+                    }
+                },
+            );
+        }));
+        self.error_ui = Some(Box::new(move |ui, err, retry, reset, style| {
+            let clear = error_strings.get(TextKey::Clear);
+            let response = style.with_buttons(
+                ui,
+                |ui| {
+                    ErrorUi::new(err)
+                        .retryable(retry.is_some())
+                        .strings(error_strings)
+                        .style(style.clone())
+                        .show(ui)
+                },
+                |ui| {
+                    if ui.button(clear).clicked() {
+                        reset();
+                    }
+                },
+            );
+            if let (true, Some(retry)) = (response.retry_clicked, retry) {
+                retry();
+            }
+        }));
+        self.spinner().empty_ui(|_| {})
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
     }
     #[must_use]
     pub fn done_ui(
         mut self,
         done_ui: impl FnOnce(&mut Ui, Result<&T, &BoxedSendError>, &mut dyn FnMut()) + 'static,
     ) -> Self {
-        self.done_ui = Some(Box::new(done_ui));
+        self.done_ui = Some(Box::new(move |ui, result, reset, _| {
+            done_ui(ui, result, reset)
+        }));
+        self
+    }
+    /// Overrides the `done_ui` for the case that the future failed. Gets the
+    /// error, a retry function if the future was set with
+    /// [`set_factory`][SetFutureBuilder::set_factory] and the reset function.
+    #[must_use]
+    pub fn error_ui(
+        mut self,
+        error_ui: impl FnOnce(&mut Ui, &BoxedSendError, Option<&mut dyn FnMut()>, &mut dyn FnMut())
+            + 'static,
+    ) -> Self {
+        self.error_ui = Some(Box::new(move |ui, err, retry, reset, _| {
+            error_ui(ui, err, retry, reset)
+        }));
         self
     }
     #[must_use]
@@ -208,42 +539,51 @@ where
     }
     #[must_use]
     pub fn spinner(mut self) -> Self {
-        self.waiting_ui = Some(Box::new(|ui| {
-            ui.add(Spinner::new());
+        self.waiting_ui = Some(Box::new(|ui, style| {
+            ui.add(style.spinner());
         }));
         self
     }
     pub fn only_poll(self) {
-        self.state.as_mut().map(|promise| {
-            promise.poll_state();
-        });
+        self.state.poll(&self.name, self.events);
     }
     pub fn show(self, ui: &mut Ui) {
-        let Some(promise) = self.state else {
+        self.state.poll(&self.name, self.events);
+        let Some(promise) = self.state.promise.as_mut() else {
             self.empty_ui.map(|empty_ui| {
                 empty_ui(ui);
             });
             return;
         };
-        if matches!(promise.poll_state(), ImmediateValueState::<T>::Updating) {
+        if self.state.running {
             self.waiting_ui.map(|waiting_ui| {
-                waiting_ui(ui);
+                waiting_ui(ui, &self.style);
             });
         } else {
             let mut reset = false;
-            match promise.get_result() {
-                Some(result) => {
+            let mut retry = false;
+            let retryable = self.state.factory.is_some();
+            match (promise.get_result(), self.error_ui) {
+                (Some(Err(err)), Some(error_ui)) => {
+                    let mut reset_fn = || reset = true;
+                    let mut retry_fn = || retry = true;
+                    let retry_fn = retryable.then_some(&mut retry_fn as &mut dyn FnMut());
+                    error_ui(ui, err, retry_fn, &mut reset_fn, &self.style);
+                }
+                (Some(result), _) => {
                     self.done_ui.map(|done_ui| {
                         let mut reset_fn = || reset = true;
-                        done_ui(ui, result, &mut reset_fn);
+                        done_ui(ui, result, &mut reset_fn, &self.style);
                     });
                 }
-                None => {
-                    *self.state = None;
+                (None, _) => {
+                    reset = true;
                 }
             }
-            if reset {
-                *self.state = None;
+            if retry {
+                self.state.retry();
+            } else if reset {
+                self.state.clear();
             }
         }
     }
@@ -0,0 +1,231 @@
+//! Lists loaded page by page from a server, fetching the next page when the
+//! list is scrolled close to its end.
+//!
+//! ```
+//! let client = self.client.clone();
+//! self.ui
+//!     .infinite_list::<Post>("feed", move |page| {
+//!         ImmediateValuePromise::new(client.posts(page))
+//!     })
+//!     .item_ui(|ui, post| {
+//!         ui.label(&post.title);
+//!     })
+//!     .show(ui);
+//! ```
+//!
+//! Pages are fetched one after another, starting at `0`, until a page comes
+//! back empty. A failed page is shown with a retry button at the end of the
+//! list.
+
+use std::any::Any;
+
+use egui::{ScrollArea, Ui};
+use lazy_async_promise::ImmediateValuePromise;
+
+use crate::{
+    clock::SharedClock, error_ui::ErrorUi, events::StateEvent, future_await::FutureState,
+    strings::LightStatesStrings, style::LightStatesStyle, UiStates,
+};
+
+/// Distance from the end of the list in points at which the next page is
+/// fetched.
+const DEFAULT_THRESHOLD: f32 = 200.;
+
+pub trait CreateInfiniteList {
+    /// `fetch_page` is called with the index of the page to load.
+    #[must_use]
+    fn infinite_list<T>(
+        &mut self,
+        name: impl Into<String>,
+        fetch_page: impl FnMut(usize) -> ImmediateValuePromise<Vec<T>> + 'static,
+    ) -> InfiniteListBuilder<T>
+    where
+        T: Send + 'static;
+}
+
+impl CreateInfiniteList for UiStates {
+    fn infinite_list<T>(
+        &mut self,
+        name: impl Into<String>,
+        fetch_page: impl FnMut(usize) -> ImmediateValuePromise<Vec<T>> + 'static,
+    ) -> InfiniteListBuilder<T>
+    where
+        T: Send + 'static,
+    {
+        let name = name.into();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        self.register_poller(name.clone(), poll_infinite_list_state::<T>);
+        let (state, events) = self.get_mut_with_events(name.clone(), InfiniteListState::default());
+        InfiniteListBuilder {
+            name,
+            state,
+            events,
+            strings,
+            style,
+            fetch_page: Box::new(fetch_page),
+            item_ui: None,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+type FetchPage<T> = Box<dyn FnMut(usize) -> ImmediateValuePromise<Vec<T>>>;
+type ItemUi<T> = Box<dyn FnMut(&mut Ui, &T)>;
+
+pub struct InfiniteListBuilder<'state, T>
+where
+    T: Send + 'static,
+{
+    name: String,
+    state: &'state mut InfiniteListState<T>,
+    events: &'state mut Vec<StateEvent>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    fetch_page: FetchPage<T>,
+    item_ui: Option<ItemUi<T>>,
+    threshold: f32,
+}
+
+impl<'state, T> InfiniteListBuilder<'state, T>
+where
+    T: Send + 'static,
+{
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Shown for every loaded item.
+    #[must_use]
+    pub fn item_ui(mut self, item_ui: impl FnMut(&mut Ui, &T) + 'static) -> Self {
+        self.item_ui = Some(Box::new(item_ui));
+        self
+    }
+    /// Distance from the end of the list in points at which the next page
+    /// is fetched, defaults to 200.
+    #[must_use]
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+    /// Drops all loaded pages, starting again with the first one.
+    #[must_use]
+    pub fn reload(self) -> Self {
+        *self.state = InfiniteListState::default();
+        self
+    }
+    /// All items loaded so far.
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.state.pages.iter().flatten()
+    }
+    /// `true` once a page came back empty.
+    pub fn is_exhausted(&self) -> bool {
+        self.state.exhausted
+    }
+    /// Shows the items in a vertical scroll area, with a spinner while a page
+    /// is loading and the error of a failed page.
+    pub fn show(self, ui: &mut Ui) {
+        let Self {
+            name,
+            state,
+            events,
+            strings,
+            style,
+            mut fetch_page,
+            mut item_ui,
+            threshold,
+        } = self;
+        state.poll(&name, events);
+
+        let mut retry = false;
+        let output = ScrollArea::vertical()
+            .id_salt(&name)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                if let Some(item_ui) = item_ui.as_mut() {
+                    for item in state.pages.iter().flatten() {
+                        item_ui(ui, item);
+                    }
+                }
+                if state.loading.is_running() {
+                    ui.add(style.spinner());
+                } else if let Some(err) = state.loading.error() {
+                    retry = ErrorUi::new(err)
+                        .retryable(true)
+                        .strings(strings)
+                        .style(style.clone())
+                        .show(ui)
+                        .retry_clicked;
+                }
+            });
+
+        let visible_end = output.state.offset.y + output.inner_rect.height();
+        let near_end = output.content_size.y - visible_end <= threshold;
+        if retry || (near_end && state.can_fetch()) {
+            let page = state.pages.len();
+            state.loading.start(fetch_page(page));
+        }
+    }
+}
+
+struct InfiniteListState<T>
+where
+    T: Send + 'static,
+{
+    pages: Vec<Vec<T>>,
+    /// Fetch of the page after the loaded ones.
+    loading: FutureState<Vec<T>>,
+    exhausted: bool,
+}
+
+impl<T> Default for InfiniteListState<T>
+where
+    T: Send + 'static,
+{
+    fn default() -> Self {
+        Self {
+            pages: vec![],
+            loading: FutureState::default(),
+            exhausted: false,
+        }
+    }
+}
+
+impl<T> InfiniteListState<T>
+where
+    T: Send + 'static,
+{
+    /// `false` while a page is loading, after a page failed and once all
+    /// pages are loaded.
+    fn can_fetch(&mut self) -> bool {
+        !self.exhausted && !self.loading.is_running() && self.loading.error().is_none()
+    }
+    /// Adds the page once it is loaded.
+    fn poll(&mut self, name: &str, events: &mut Vec<StateEvent>) {
+        self.loading.poll(name, events);
+        let Some(page) = self.loading.take_value() else {
+            return;
+        };
+        self.loading.clear();
+        if page.is_empty() {
+            self.exhausted = true;
+        } else {
+            self.pages.push(page);
+        }
+    }
+}
+
+fn poll_infinite_list_state<T>(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    _: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) where
+    T: Send + 'static,
+{
+    if let Some(state) = state.downcast_mut::<InfiniteListState<T>>() {
+        state.poll(name, events);
+    }
+}
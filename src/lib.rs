@@ -1,23 +1,176 @@
 use std::{any::Any, collections::HashMap};
 
+use clock::{Clock, SharedClock};
 use egui::Ui;
+use events::StateEvent;
+use modal::ModalStack;
+use notifications::NotificationsState;
+use signal::Invalidations;
+use strings::LightStatesStrings;
+use style::LightStatesStyle;
 
 mod other;
+pub mod clock;
+pub mod computed;
+pub mod confirm;
+pub mod cooldown;
+pub mod deferred;
+pub mod error_ui;
+pub mod events;
+pub mod flash;
+pub mod form;
+pub mod fsm;
+pub mod infinite_list;
+pub mod modal;
+pub mod notifications;
 pub mod promise_await;
+pub mod rate_limit;
+pub mod selection;
+pub mod signal;
 pub mod default_promise_await;
+pub mod stopwatch;
+pub mod strings;
+pub mod style;
+pub mod table_view;
 pub mod timer;
+pub mod timer_sequence;
+pub mod timer_widgets;
+pub mod tween;
+pub mod undoable;
+pub mod wizard;
 pub mod future_await;
+#[cfg(test)]
+mod test_support;
+
+/// Number of events kept for [`UiStates::drain_events`], older ones are
+/// dropped.
+const MAX_QUEUED_EVENTS: usize = 1000;
 
 pub trait UiWithState {
     fn ui(&mut self, ui: &mut Ui);
 }
 
+/// Function used to poll a state in the background, independent of it being
+/// shown. Gets the name and the type erased state of the entry it was
+/// registered for.
+pub(crate) type StatePoller =
+    fn(&str, &mut (dyn Any + Send), &SharedClock, &mut Vec<StateEvent>);
+
 #[derive(Default)]
 pub struct UiStates {
     pub(crate) states: HashMap<String, Box<dyn Any + Send + 'static>>,
+    pub(crate) pollers: HashMap<String, StatePoller>,
+    pub(crate) events: Vec<StateEvent>,
+    pub(crate) strings: LightStatesStrings,
+    pub(crate) style: LightStatesStyle,
+    pub(crate) clock: SharedClock,
+    pub(crate) notifications: NotificationsState,
+    pub(crate) modals: ModalStack,
+    pub(crate) invalidations: Invalidations,
 }
 
 impl UiStates {
+    /// Polls all of the states that need to progress even when they are not
+    /// shown (like running futures), firing their callbacks and queueing their
+    /// events.
+    ///
+    /// Is called by [`drain_events`][UiStates::drain_events], so calling that
+    /// once per frame is enough.
+    pub fn poll(&mut self) {
+        self.apply_invalidations();
+        let Self {
+            states,
+            pollers,
+            events,
+            clock,
+            ..
+        } = self;
+        for (name, poller) in pollers.iter() {
+            if let Some(state) = states.get_mut(name) {
+                poller(name, state.as_mut(), clock, events);
+            }
+        }
+        self.trim_events();
+    }
+
+    /// Polls all states and then returns every [`StateEvent`] that happened
+    /// since the last call.
+    ///
+    /// Only the latest 1000 events are kept, so apps that never call this do
+    /// not keep collecting them.
+    ///
+    /// ```
+    /// for event in self.ui.drain_events() {
+    ///     if let StateEvent::FutureCompleted { name, .. } = event {
+    ///         println!("{name} is done");
+    ///     }
+    /// }
+    /// ```
+    pub fn drain_events(&mut self) -> Vec<StateEvent> {
+        self.poll();
+        std::mem::take(&mut self.events)
+    }
+
+    /// Replaces the texts used by the built-in UIs.
+    pub fn set_strings(&mut self, strings: LightStatesStrings) {
+        self.strings = strings;
+    }
+
+    pub fn strings_mut(&mut self) -> &mut LightStatesStrings {
+        &mut self.strings
+    }
+
+    /// Replaces the style used by the built-in UIs.
+    pub fn set_style(&mut self, style: LightStatesStyle) {
+        self.style = style;
+    }
+
+    pub fn style_mut(&mut self) -> &mut LightStatesStyle {
+        &mut self.style
+    }
+
+    /// Replaces the clock used by all time based states.
+    pub fn set_clock(&mut self, clock: impl Clock) {
+        self.clock = SharedClock::new(clock);
+    }
+
+    /// Drops the states invalidated by a [`Signal`][signal::Signal] since the
+    /// last access.
+    fn apply_invalidations(&mut self) {
+        for name in self.invalidations.take() {
+            self.states.remove(&name);
+            self.pollers.remove(&name);
+        }
+    }
+
+    fn trim_events(&mut self) {
+        let excess = self.events.len().saturating_sub(MAX_QUEUED_EVENTS);
+        self.events.drain(..excess);
+    }
+
+    pub(crate) fn register_poller(&mut self, name: impl Into<String>, poller: StatePoller) {
+        self.pollers.insert(name.into(), poller);
+    }
+
+    pub(crate) fn get_mut_with_events<'state, StateType>(
+        &'state mut self,
+        name: String,
+        init_state: StateType,
+    ) -> (&'state mut StateType, &'state mut Vec<StateEvent>)
+    where
+        StateType: Send + 'static,
+    {
+        self.apply_invalidations();
+        self.trim_events();
+        let state = self
+            .states
+            .entry(name)
+            .or_insert(Box::new(init_state))
+            .downcast_mut::<StateType>()
+            .unwrap();
+        (state, &mut self.events)
+    }
+
     pub(crate) fn get_mut<'state, StateType>(
         &'state mut self,
         name: String,
@@ -26,6 +179,7 @@ impl UiStates {
     where
         StateType: Send + 'static,
     {
+        self.apply_invalidations();
         self.states
             .entry(name)
             .or_insert(Box::new(init_state))
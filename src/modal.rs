@@ -0,0 +1,325 @@
+//! Dialogs that are opened with a payload from anywhere and hand a result
+//! back once they are closed, replacing a `bool` per dialog.
+//!
+//! ```
+//! if ui.button("rename").clicked() {
+//!     self.ui
+//!         .modal::<String, String>("rename")
+//!         .open(self.item.name.clone());
+//! }
+//!
+//! let client = self.client.clone();
+//! let renamed = self
+//!     .ui
+//!     .modal::<String, String>("rename")
+//!     .title("Rename item")
+//!     .show(ctx, |ui, name| {
+//!         ui.text_edit_singleline(name);
+//!         if ui.button("save").clicked() {
+//!             // stays open until the server accepted the name
+//!             return ModalResult::CloseAfter(ImmediateValuePromise::new(
+//!                 client.rename(name.clone()),
+//!             ));
+//!         }
+//!         if ui.button("cancel").clicked() {
+//!             return ModalResult::Cancel;
+//!         }
+//!         ModalResult::Open
+//!     });
+//! if let Some(name) = renamed {
+//!     self.item.name = name;
+//! }
+//! ```
+//!
+//! Opening a modal closes all others, unless it is opened
+//! [`stacked`][ModalBuilder::stacked] on top of them. Only the top modal can
+//! be interacted with and is closed by escape, everything behind it is
+//! dimmed and does not get clicks.
+
+use egui::{Align2, Area, Color32, Context, Id, Key, LayerId, Modifiers, Order, Sense, Ui, Window};
+use lazy_async_promise::ImmediateValuePromise;
+
+use crate::{
+    events::StateEvent,
+    future_await::{FutureState, FutureStatusBuilder},
+    strings::LightStatesStrings,
+    style::LightStatesStyle,
+    UiStates,
+};
+
+const BACKDROP_ALPHA: u8 = 96;
+
+/// What a modal should do after its contents were shown.
+pub enum ModalResult<R> {
+    /// Stays open.
+    Open,
+    /// Closes and returns the value from [`ModalBuilder::show`].
+    Close(R),
+    /// Stays open while the future runs and closes with its value once it
+    /// succeeded. Errors are shown in the modal.
+    CloseAfter(ImmediateValuePromise<R>),
+    /// Closes without a value.
+    Cancel,
+}
+
+pub trait Modals {
+    /// The payload and result types have to be the same wherever the modal of
+    /// that name is used.
+    #[must_use]
+    fn modal<P, R>(&mut self, name: impl Into<String>) -> ModalBuilder<P, R>
+    where
+        P: Send + 'static,
+        R: Send + 'static;
+}
+
+impl Modals for UiStates {
+    fn modal<P, R>(&mut self, name: impl Into<String>) -> ModalBuilder<P, R>
+    where
+        P: Send + 'static,
+        R: Send + 'static,
+    {
+        let name = name.into();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        // creates the state, then borrows it next to the stack
+        self.get_mut_with_events(name.clone(), ModalState::<P, R>::default());
+        let Self {
+            states,
+            events,
+            modals: stack,
+            ..
+        } = self;
+        let state = states
+            .get_mut(&name)
+            .and_then(|state| state.downcast_mut::<ModalState<P, R>>())
+            .unwrap();
+        ModalBuilder {
+            title: name.clone(),
+            name,
+            state,
+            events,
+            stack,
+            stacked: false,
+            strings,
+            style,
+        }
+    }
+}
+
+pub struct ModalBuilder<'state, P, R>
+where
+    P: Send + 'static,
+    R: Send + 'static,
+{
+    name: String,
+    state: &'state mut ModalState<P, R>,
+    events: &'state mut Vec<StateEvent>,
+    stack: &'state mut ModalStack,
+    title: String,
+    stacked: bool,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+}
+
+impl<'state, P, R> ModalBuilder<'state, P, R>
+where
+    P: Send + 'static,
+    R: Send + 'static,
+{
+    /// Opens the modal on top of the already open ones instead of closing
+    /// them.
+    #[must_use]
+    pub fn stacked(mut self) -> Self {
+        self.stacked = true;
+        self
+    }
+    /// Title of the window, defaults to the name.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    pub fn is_open(&self) -> bool {
+        self.state.payload.is_some() && self.stack.contains(&self.name)
+    }
+    /// Opens the modal with the payload, replacing the payload if it is
+    /// already open.
+    pub fn open(self, payload: P) {
+        *self.state = ModalState {
+            payload: Some(payload),
+            ..Default::default()
+        };
+        self.stack.push(&self.name, self.stacked);
+    }
+    pub fn close(self) {
+        self.stack.remove(&self.name);
+        *self.state = ModalState::default();
+    }
+    /// Shows the modal while it is open. Returns the value it was closed
+    /// with, once.
+    pub fn show(
+        self,
+        ctx: &Context,
+        contents: impl FnOnce(&mut Ui, &mut P) -> ModalResult<R>,
+    ) -> Option<R> {
+        let Self {
+            name,
+            state,
+            events,
+            stack,
+            title,
+            strings,
+            style,
+            ..
+        } = self;
+        if !stack.contains(&name) {
+            *state = ModalState::default();
+            return None;
+        }
+        state.closing.poll(&name, events);
+        if state.submitting && !state.closing.is_running() {
+            state.submitting = false;
+            if let Some(value) = state.closing.take_value() {
+                stack.remove(&name);
+                *state = ModalState::default();
+                return Some(value);
+            }
+        }
+        let Some(payload) = state.payload.as_mut() else {
+            stack.remove(&name);
+            return None;
+        };
+
+        let id = Id::new(&name).with("modal");
+        let is_top = stack.is_top(&name);
+        if is_top {
+            backdrop(ctx, id);
+        }
+        let awaiting = state.closing.is_running();
+        let mut result = ModalResult::Open;
+        let mut open = true;
+        Window::new(title)
+            .id(id)
+            .open(&mut open)
+            .enabled(is_top)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0., 0.])
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(!awaiting, |ui| {
+                    result = contents(ui, payload);
+                });
+                FutureStatusBuilder::new(name.clone(), &mut state.closing, events, strings, style)
+                    .default()
+                    .show(ui);
+            });
+        if is_top {
+            ctx.move_to_top(LayerId::new(Order::Middle, id));
+        }
+        // the status UI might have seen the future finish, so the modal only
+        // closes in the next frame
+        if state.submitting && !state.closing.is_running() {
+            ctx.request_repaint();
+        }
+        if awaiting {
+            return None;
+        }
+        let escaped =
+            is_top && ctx.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Escape));
+        if !open || escaped {
+            result = ModalResult::Cancel;
+        }
+
+        match result {
+            ModalResult::Open => None,
+            ModalResult::Close(value) => {
+                stack.remove(&name);
+                *state = ModalState::default();
+                Some(value)
+            }
+            ModalResult::CloseAfter(promise) => {
+                state.closing.start(promise);
+                state.submitting = true;
+                None
+            }
+            ModalResult::Cancel => {
+                stack.remove(&name);
+                *state = ModalState::default();
+                None
+            }
+        }
+    }
+}
+
+/// Dims the screen and takes the clicks meant for everything behind the
+/// top modal, which is moved above it once shown.
+fn backdrop(ctx: &Context, id: Id) {
+    let screen = ctx.screen_rect();
+    let backdrop_id = id.with("backdrop");
+    Area::new(backdrop_id)
+        .order(Order::Middle)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            ui.painter()
+                .rect_filled(screen, 0., Color32::from_black_alpha(BACKDROP_ALPHA));
+            ui.allocate_rect(screen, Sense::click_and_drag());
+        });
+    ctx.move_to_top(LayerId::new(Order::Middle, backdrop_id));
+}
+
+/// Names of the open modals, the last one is on top. Shared by all modals
+/// of a [`UiStates`].
+#[derive(Default)]
+pub(crate) struct ModalStack {
+    names: Vec<String>,
+}
+
+impl ModalStack {
+    fn push(&mut self, name: &str, stacked: bool) {
+        if !stacked {
+            self.names.clear();
+        }
+        self.names.retain(|open| open != name);
+        self.names.push(name.to_string());
+    }
+    fn remove(&mut self, name: &str) {
+        self.names.retain(|open| open != name);
+    }
+    fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|open| open == name)
+    }
+    fn is_top(&self, name: &str) -> bool {
+        self.names.last().is_some_and(|top| top == name)
+    }
+}
+
+struct ModalState<P, R>
+where
+    R: Send + 'static,
+{
+    payload: Option<P>,
+    /// Future set through [`ModalResult::CloseAfter`].
+    closing: FutureState<R>,
+    /// Set from starting the closing future until it was seen finishing, the
+    /// status UI polls the future as well.
+    submitting: bool,
+}
+
+impl<P, R> Default for ModalState<P, R>
+where
+    R: Send + 'static,
+{
+    fn default() -> Self {
+        Self {
+            payload: None,
+            closing: FutureState::default(),
+            submitting: false,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Toasts that are pushed from anywhere and shown stacked in a corner of the
+//! screen until they expire.
+//!
+//! ```
+//! // a notifier can be moved into callbacks, like the ones of `set_future`
+//! let notifier = self.ui.notifier();
+//! self.ui
+//!     .set_future("save")
+//!     .on_complete(move |result| match result {
+//!         Ok(_) => notifier.push(Toast::success("saved")),
+//!         Err(err) => notifier.push(Toast::error(err.0.to_string())),
+//!     })
+//!     .set(future);
+//!
+//! // once per frame, after all other ui
+//! self.ui.notifications_ui(ctx);
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use chrono::Duration;
+use egui::{Align2, Area, Context, Frame, Id, Order, RichText, Ui};
+
+use crate::{strings::TextKey, style::LightStatesStyle, timer::TimerState, UiStates};
+
+const DEFAULT_TOAST_SECONDS: i64 = 4;
+const FADE_OUT_MILLIS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Error,
+}
+
+pub struct ToastAction {
+    label: String,
+    on_click: Box<dyn FnOnce() + Send>,
+}
+
+pub struct Toast {
+    kind: ToastKind,
+    message: String,
+    duration: Duration,
+    actions: Vec<ToastAction>,
+}
+
+impl Toast {
+    pub fn new(kind: ToastKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            duration: Duration::seconds(DEFAULT_TOAST_SECONDS),
+            actions: vec![],
+        }
+    }
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Info, message)
+    }
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Success, message)
+    }
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Error, message)
+    }
+    /// How long the toast is shown, defaults to four seconds.
+    #[must_use]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+    /// Adds a button to the toast, clicking it runs `on_click` and closes the
+    /// toast.
+    #[must_use]
+    pub fn action(
+        mut self,
+        label: impl Into<String>,
+        on_click: impl FnOnce() + Send + 'static,
+    ) -> Self {
+        self.actions.push(ToastAction {
+            label: label.into(),
+            on_click: Box::new(on_click),
+        });
+        self
+    }
+}
+
+/// Handle for pushing toasts that can be cloned and sent to other threads.
+/// The toasts are picked up by the next call to
+/// [`notifications_ui`][Notify::notifications_ui], pushing one requests a
+/// repaint once that was called the first time.
+#[derive(Clone, Default)]
+pub struct Notifier {
+    queue: Arc<Mutex<NotifierQueue>>,
+}
+
+#[derive(Default)]
+struct NotifierQueue {
+    toasts: Vec<Toast>,
+    /// Set by [`Notify::notifications_ui`], to wake it up for new toasts.
+    ctx: Option<Context>,
+}
+
+impl Notifier {
+    pub fn push(&self, toast: Toast) {
+        let ctx = match self.queue.lock() {
+            Ok(mut queue) => {
+                queue.toasts.push(toast);
+                queue.ctx.clone()
+            }
+            Err(_) => None,
+        };
+        // repaints without the lock, in case the ui is waiting for it
+        if let Some(ctx) = ctx {
+            ctx.request_repaint();
+        }
+    }
+    fn take(&self, ctx: &Context) -> Vec<Toast> {
+        self.queue
+            .lock()
+            .map(|mut queue| {
+                queue.ctx.get_or_insert_with(|| ctx.clone());
+                std::mem::take(&mut queue.toasts)
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub trait Notify {
+    fn notifier(&self) -> Notifier;
+
+    fn notify(&mut self, toast: Toast);
+
+    /// Shows all active toasts stacked in the bottom right corner, should be
+    /// called once per frame.
+    fn notifications_ui(&mut self, ctx: &Context);
+}
+
+impl Notify for UiStates {
+    fn notifier(&self) -> Notifier {
+        self.notifications.notifier.clone()
+    }
+
+    fn notify(&mut self, toast: Toast) {
+        self.notifications.notifier.push(toast);
+    }
+
+    fn notifications_ui(&mut self, ctx: &Context) {
+        let now = self.clock.now();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let notifications = &mut self.notifications;
+
+        for toast in notifications.notifier.take(ctx) {
+            let mut timer = TimerState::from(toast.duration);
+            timer.start(now);
+            notifications.active.push(ActiveToast { toast, timer });
+        }
+        notifications
+            .active
+            .retain_mut(|active| !active.timer.finish_if_elapsed(now));
+        if notifications.active.is_empty() {
+            return;
+        }
+
+        let mut closed = vec![];
+        let mut clicked = vec![];
+        Area::new(Id::new("egui_light_states_notifications"))
+            .anchor(Align2::RIGHT_BOTTOM, [-8., -8.])
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                for (index, active) in notifications.active.iter().enumerate() {
+                    let remaining = active
+                        .timer
+                        .remaining(now)
+                        .unwrap_or_else(Duration::zero)
+                        .num_milliseconds();
+                    ui.scope(|ui| {
+                        ui.multiply_opacity((remaining as f32 / FADE_OUT_MILLIS as f32).min(1.));
+                        Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(toast_text(&style, ui, &active.toast));
+                                for (action_index, action) in
+                                    active.toast.actions.iter().enumerate()
+                                {
+                                    if ui.button(&action.label).clicked() {
+                                        clicked.push((index, action_index));
+                                    }
+                                }
+                                if ui.small_button(strings.get(TextKey::Close)).clicked() {
+                                    closed.push(index);
+                                }
+                            });
+                        });
+                    });
+                }
+            });
+
+        for (index, action_index) in clicked.iter().copied() {
+            let action = notifications.active[index]
+                .toast
+                .actions
+                .swap_remove(action_index);
+            (action.on_click)();
+        }
+        closed.extend(clicked.into_iter().map(|(index, _)| index));
+        closed.sort_unstable();
+        closed.dedup();
+        for index in closed.into_iter().rev() {
+            notifications.active.remove(index);
+        }
+
+        // every frame while a toast fades out, otherwise once the next one
+        // starts to
+        let next_fade = notifications
+            .active
+            .iter()
+            .filter_map(|active| active.timer.remaining(now))
+            .min()
+            .map(|remaining| remaining - Duration::milliseconds(FADE_OUT_MILLIS));
+        match next_fade {
+            Some(until_fade) if until_fade > Duration::zero() => {
+                ctx.request_repaint_after(until_fade.to_std().unwrap_or_default());
+            }
+            Some(_) => ctx.request_repaint(),
+            None => {}
+        }
+    }
+}
+
+fn toast_text(style: &LightStatesStyle, ui: &Ui, toast: &Toast) -> RichText {
+    match toast.kind {
+        ToastKind::Info => RichText::new(&toast.message),
+        ToastKind::Success => style.success_text(ui, &toast.message),
+        ToastKind::Error => style.error_text(ui, &toast.message),
+    }
+}
+
+/// Toasts of a [`UiStates`], stored outside of the named states since there
+/// is only one queue.
+#[derive(Default)]
+pub(crate) struct NotificationsState {
+    notifier: Notifier,
+    active: Vec<ActiveToast>,
+}
+
+struct ActiveToast {
+    toast: Toast,
+    /// Runs for the duration of the toast.
+    timer: TimerState,
+}
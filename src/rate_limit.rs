@@ -0,0 +1,171 @@
+//! Debouncing of values and throttling of actions, both built on top of the
+//! [`TimerState`] of the [`timer`][crate::timer] module.
+//!
+//! ```
+//! ui.text_edit_singleline(&mut self.search);
+//! if let Some(search) = self.ui.debounce(
+//!     ui.ctx(),
+//!     "search",
+//!     self.search.clone(),
+//!     Duration::milliseconds(300),
+//! ) {
+//!     self.ui.set_future("search_results").set(search_for(search));
+//! }
+//!
+//! if ui.button("refresh").clicked() && self.ui.throttle("refresh", Duration::seconds(5)) {
+//!     self.refresh();
+//! }
+//! ```
+
+use chrono::{DateTime, Duration, Local};
+use egui::Context;
+
+use crate::{timer::TimerState, UiStates};
+
+pub trait RateLimit {
+    /// Returns the value once it has not changed for `delay`, and only once
+    /// per change. This includes the first value seen, so a restored value is
+    /// returned without being edited first.
+    ///
+    /// Schedules a repaint for when the delay is over, so the value is
+    /// returned even without any further input.
+    fn debounce<T>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        value: T,
+        delay: Duration,
+    ) -> Option<T>
+    where
+        T: PartialEq + Clone + Send + 'static;
+
+    /// Returns `true` at most once per `interval`, meant to guard an action.
+    ///
+    /// ```
+    /// if self.ui.throttle("save", Duration::seconds(1)) {
+    ///     self.save();
+    /// }
+    /// ```
+    fn throttle(&mut self, name: impl Into<String>, interval: Duration) -> bool;
+}
+
+impl RateLimit for UiStates {
+    fn debounce<T>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        value: T,
+        delay: Duration,
+    ) -> Option<T>
+    where
+        T: PartialEq + Clone + Send + 'static,
+    {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), DebounceState::<T>::from(delay));
+        state.set_delay(delay);
+        state.update(ctx, now, value)
+    }
+
+    fn throttle(&mut self, name: impl Into<String>, interval: Duration) -> bool {
+        let now = self.clock.now();
+        let timer = self.get_mut(name.into(), TimerState::from(interval));
+        timer.set_duration(interval);
+        timer.finish_if_elapsed(now);
+        if timer.is_running() {
+            false
+        } else {
+            timer.start(now);
+            true
+        }
+    }
+}
+
+/// State of [`RateLimit::debounce`], also used by other states debouncing a
+/// value of their own.
+pub(crate) struct DebounceState<T> {
+    /// Last value passed in.
+    value: Option<T>,
+    /// Running while the last value has not been stable for long enough.
+    timer: TimerState,
+}
+
+impl<T> From<Duration> for DebounceState<T> {
+    fn from(delay: Duration) -> Self {
+        Self {
+            value: None,
+            timer: TimerState::from(delay),
+        }
+    }
+}
+
+impl<T> DebounceState<T>
+where
+    T: PartialEq + Clone,
+{
+    pub(crate) fn set_delay(&mut self, delay: Duration) {
+        self.timer.set_duration(delay);
+    }
+    /// Takes the latest value, returns it once it has not changed for the
+    /// delay. Schedules a repaint for when the delay is over.
+    pub(crate) fn update(&mut self, ctx: &Context, now: DateTime<Local>, value: T) -> Option<T> {
+        if self.value.as_ref() != Some(&value) {
+            self.value = Some(value);
+            self.timer.start(now);
+        }
+
+        if self.timer.finish_if_elapsed(now) {
+            return self.value.clone();
+        }
+        if let Some(remaining) = self.timer.remaining(now) {
+            ctx.request_repaint_after(remaining.to_std().unwrap_or_default());
+        }
+        None
+    }
+    /// Takes the value as already stable, without returning it.
+    pub(crate) fn settle(&mut self, value: T) {
+        self.value = Some(value);
+        self.timer.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use egui::Context;
+
+    use super::RateLimit;
+    use crate::test_support::ManualClock;
+
+    #[test]
+    fn debounce_returns_the_value_once_it_settled() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+        let delay = Duration::milliseconds(300);
+
+        assert_eq!(states.debounce(&ctx, "search", "a", delay), None);
+        clock.advance(Duration::milliseconds(200));
+        assert_eq!(states.debounce(&ctx, "search", "ab", delay), None);
+        clock.advance(Duration::milliseconds(200));
+        assert_eq!(states.debounce(&ctx, "search", "ab", delay), None);
+        clock.advance(Duration::milliseconds(100));
+        assert_eq!(states.debounce(&ctx, "search", "ab", delay), Some("ab"));
+        clock.advance(Duration::seconds(1));
+        assert_eq!(states.debounce(&ctx, "search", "ab", delay), None);
+    }
+
+    #[test]
+    fn throttle_allows_one_call_per_interval() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let interval = Duration::seconds(5);
+
+        assert!(states.throttle("refresh", interval));
+        assert!(!states.throttle("refresh", interval));
+        clock.advance(Duration::seconds(4));
+        assert!(!states.throttle("refresh", interval));
+        clock.advance(Duration::seconds(1));
+        assert!(states.throttle("refresh", interval));
+        assert!(!states.throttle("refresh", interval));
+    }
+}
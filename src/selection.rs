@@ -0,0 +1,328 @@
+//! Single or multi selection over a list of ids, with shift ranges,
+//! ctrl/cmd toggling and keyboard navigation.
+//!
+//! ```
+//! let ids: Vec<u64> = self.files.iter().map(|file| file.id).collect();
+//! let mut selection = self.ui.selection::<u64>("files").items(ids);
+//! selection.keyboard(ui);
+//! for file in &self.files {
+//!     let response = ui.selectable_label(selection.is_selected(&file.id), &file.name);
+//!     selection.response(&response, file.id);
+//! }
+//! ui.label(format!("{} selected", selection.len()));
+//! ```
+//!
+//! Giving the current items through [`items`][Selection::items] removes the
+//! ids that no longer exist from the selection and is needed for ranges,
+//! select all and the keyboard navigation.
+
+use std::{collections::HashSet, hash::Hash};
+
+use egui::{Key, Modifiers, Response, Ui};
+
+use crate::UiStates;
+
+/// Bounds for the ids of a [`Selection`].
+pub trait SelectionIdTraits
+where
+    Self: Clone + Eq + Hash + Send + 'static,
+{
+}
+
+impl<T> SelectionIdTraits for T where T: Clone + Eq + Hash + Send + 'static {}
+
+pub trait CreateSelection {
+    #[must_use]
+    fn selection<Id>(&mut self, name: impl Into<String>) -> Selection<Id>
+    where
+        Id: SelectionIdTraits;
+}
+
+impl CreateSelection for UiStates {
+    fn selection<Id>(&mut self, name: impl Into<String>) -> Selection<Id>
+    where
+        Id: SelectionIdTraits,
+    {
+        let state = self.get_mut(name.into(), SelectionState::default());
+        Selection {
+            state,
+            items: vec![],
+            multiple: true,
+        }
+    }
+}
+
+pub struct Selection<'state, Id>
+where
+    Id: SelectionIdTraits,
+{
+    state: &'state mut SelectionState<Id>,
+    /// Current items in the order they are shown.
+    items: Vec<Id>,
+    multiple: bool,
+}
+
+impl<'state, Id> Selection<'state, Id>
+where
+    Id: SelectionIdTraits,
+{
+    /// Sets the current items in the order they are shown, removing all
+    /// other ids from the selection.
+    #[must_use]
+    pub fn items(mut self, items: impl IntoIterator<Item = Id>) -> Self {
+        self.items = items.into_iter().collect();
+        let existing: HashSet<&Id> = self.items.iter().collect();
+        self.state.selected.retain(|id| existing.contains(id));
+        if self
+            .state
+            .anchor
+            .as_ref()
+            .is_some_and(|id| !existing.contains(id))
+        {
+            self.state.anchor = None;
+        }
+        if self
+            .state
+            .cursor
+            .as_ref()
+            .is_some_and(|id| !existing.contains(id))
+        {
+            self.state.cursor = None;
+        }
+        self
+    }
+    /// Only allows a single selected id, modifiers are ignored.
+    #[must_use]
+    pub fn single(mut self) -> Self {
+        self.multiple = false;
+        if self.state.selected.len() > 1 {
+            let cursor = self.state.cursor.clone();
+            self.state.selected.retain(|id| cursor.as_ref() == Some(id));
+        }
+        self
+    }
+    pub fn is_selected(&self, id: &Id) -> bool {
+        self.state.selected.contains(id)
+    }
+    /// Selected ids in the order of the [`items`][Selection::items], in no
+    /// particular order if none were given.
+    pub fn selected(&self) -> Vec<&Id> {
+        if self.items.is_empty() {
+            return self.state.selected.iter().collect();
+        }
+        self.items
+            .iter()
+            .filter(|id| self.state.selected.contains(*id))
+            .collect()
+    }
+    pub fn len(&self) -> usize {
+        self.state.selected.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.state.selected.is_empty()
+    }
+    /// Last clicked or navigated to id.
+    pub fn cursor(&self) -> Option<&Id> {
+        self.state.cursor.as_ref()
+    }
+    /// Handles a click on the item: a plain click selects only it, ctrl/cmd
+    /// toggles it and shift selects the range from the last clicked item,
+    /// added to the selection if ctrl/cmd is held as well. Without a last
+    /// clicked item, shift makes this one the start of the range.
+    pub fn click(&mut self, id: Id, modifiers: Modifiers) {
+        if !self.multiple {
+            self.select_only(id);
+        } else if modifiers.shift {
+            let anchor = self.state.anchor.get_or_insert_with(|| id.clone()).clone();
+            if !modifiers.command {
+                self.state.selected.clear();
+            }
+            self.select_range(&anchor, &id);
+            self.state.cursor = Some(id);
+        } else if modifiers.command {
+            self.toggle(id);
+        } else {
+            self.select_only(id);
+        }
+    }
+    /// Calls [`click`][Selection::click] if the response was clicked, returns
+    /// whether it was.
+    pub fn response(&mut self, response: &Response, id: Id) -> bool {
+        if !response.clicked() {
+            return false;
+        }
+        let modifiers = response.ctx.input(|input| input.modifiers);
+        self.click(id, modifiers);
+        true
+    }
+    /// Handles the arrow keys, with shift extending the selection, ctrl/cmd+A
+    /// and escape. Does nothing while a widget has keyboard focus, so text
+    /// edits keep their keys. Escape only clears the selection while the
+    /// pointer is over the `ui`, so closing a dialog keeps it.
+    pub fn keyboard(&mut self, ui: &Ui) {
+        if ui.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        let hovered = ui.rect_contains_pointer(ui.max_rect());
+        let (down, up, all, escape, shift) = ui.input_mut(|input| {
+            (
+                // consumed so a surrounding scroll area does not scroll too,
+                // shift is ignored when matching
+                input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                input.consume_key(Modifiers::COMMAND, Key::A),
+                hovered && input.consume_key(Modifiers::NONE, Key::Escape),
+                input.modifiers.shift,
+            )
+        });
+        if all && self.multiple {
+            self.select_all();
+        }
+        if escape {
+            self.clear();
+        }
+        let step = match (down, up) {
+            (true, false) => 1,
+            (false, true) => -1,
+            _ => return,
+        };
+        let Some(next) = self.neighbour(step) else {
+            return;
+        };
+        let modifiers = if shift {
+            Modifiers::SHIFT
+        } else {
+            Modifiers::NONE
+        };
+        self.click(next, modifiers);
+    }
+    pub fn select(&mut self, id: Id) {
+        if !self.multiple {
+            self.state.selected.clear();
+        }
+        self.state.selected.insert(id.clone());
+        self.state.anchor = Some(id.clone());
+        self.state.cursor = Some(id);
+    }
+    pub fn deselect(&mut self, id: &Id) {
+        self.state.selected.remove(id);
+    }
+    pub fn toggle(&mut self, id: Id) {
+        if self.is_selected(&id) {
+            self.deselect(&id);
+            self.state.anchor = Some(id.clone());
+            self.state.cursor = Some(id);
+        } else {
+            self.select(id);
+        }
+    }
+    /// Selects all [`items`][Selection::items].
+    pub fn select_all(&mut self) {
+        self.state.selected.extend(self.items.iter().cloned());
+    }
+    pub fn clear(&mut self) {
+        self.state.selected.clear();
+        self.state.anchor = None;
+    }
+    fn select_only(&mut self, id: Id) {
+        self.state.selected.clear();
+        self.select(id);
+    }
+    /// Selects all items between the two ids, including both. Only selects
+    /// `to` if either is not one of the items.
+    fn select_range(&mut self, from: &Id, to: &Id) {
+        let from_index = self.items.iter().position(|id| id == from);
+        let to_index = self.items.iter().position(|id| id == to);
+        let (Some(from_index), Some(to_index)) = (from_index, to_index) else {
+            self.state.selected.insert(to.clone());
+            return;
+        };
+        let range = from_index.min(to_index)..=from_index.max(to_index);
+        self.state
+            .selected
+            .extend(self.items[range].iter().cloned());
+    }
+    /// Item `step` places away from the cursor, the first item if there is no
+    /// cursor.
+    fn neighbour(&self, step: isize) -> Option<Id> {
+        let index = match self
+            .state
+            .cursor
+            .as_ref()
+            .and_then(|cursor| self.items.iter().position(|id| id == cursor))
+        {
+            Some(index) => index.checked_add_signed(step)?,
+            None => 0,
+        };
+        self.items.get(index).cloned()
+    }
+}
+
+struct SelectionState<Id>
+where
+    Id: SelectionIdTraits,
+{
+    selected: HashSet<Id>,
+    /// Start of shift ranges, the last item clicked without shift.
+    anchor: Option<Id>,
+    cursor: Option<Id>,
+}
+
+impl<Id> Default for SelectionState<Id>
+where
+    Id: SelectionIdTraits,
+{
+    fn default() -> Self {
+        Self {
+            selected: HashSet::new(),
+            anchor: None,
+            cursor: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Modifiers;
+
+    use super::CreateSelection;
+    use crate::UiStates;
+
+    fn selected(states: &mut UiStates) -> Vec<u32> {
+        let selection = states.selection::<u32>("list").items(0..10);
+        selection.selected().into_iter().copied().collect()
+    }
+
+    #[test]
+    fn shift_click_selects_from_the_last_click() {
+        let mut states = UiStates::default();
+        let mut selection = states.selection::<u32>("list").items(0..10);
+        selection.click(2, Modifiers::NONE);
+        selection.click(5, Modifiers::SHIFT);
+        assert_eq!(selected(&mut states), vec![2, 3, 4, 5]);
+
+        // the anchor stays, so shift-clicking again moves the end
+        let mut selection = states.selection::<u32>("list").items(0..10);
+        selection.click(0, Modifiers::SHIFT);
+        assert_eq!(selected(&mut states), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn first_shift_click_sets_the_anchor() {
+        let mut states = UiStates::default();
+        let mut selection = states.selection::<u32>("list").items(0..10);
+        selection.click(3, Modifiers::SHIFT);
+        selection.click(6, Modifiers::SHIFT);
+        assert_eq!(selected(&mut states), vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn command_click_toggles() {
+        let mut states = UiStates::default();
+        let mut selection = states.selection::<u32>("list").items(0..10);
+        selection.click(1, Modifiers::NONE);
+        selection.click(4, Modifiers::COMMAND);
+        selection.click(1, Modifiers::COMMAND);
+        assert_eq!(selected(&mut states), vec![4]);
+    }
+}
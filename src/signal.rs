@@ -0,0 +1,236 @@
+//! Values shared by name between widgets, with a version for change
+//! detection and subscribers that are notified when the value changes.
+//!
+//! ```
+//! // in one panel
+//! let project = self.ui.signal::<Option<ProjectId>>("project");
+//! project.repaint_on_change(ctx);
+//! project.invalidate_on_change("project_stats");
+//! if ui.button("open").clicked() {
+//!     project.set(Some(id));
+//! }
+//!
+//! // in another panel
+//! let project = self.ui.signal::<Option<ProjectId>>("project");
+//! if project.changed_since(&mut self.seen_project) {
+//!     self.scroll_to_top = true;
+//! }
+//! if let Some(id) = project.get() {
+//!     // computed again only after the project changed
+//!     let stats = self.ui.computed("project_stats", (), || self.store.stats(id));
+//!     ui.label(format!("{} files", stats.files));
+//! }
+//! ```
+//!
+//! A [`Signal`] is a handle that can be cloned and sent to other threads,
+//! like into the callbacks of `set_future`. Setting a value equal to the
+//! current one changes nothing.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use egui::Context;
+
+use crate::{UiStates, UserStateTraits};
+
+/// Bounds for the values of a [`Signal`].
+pub trait SignalValueTraits
+where
+    Self: UserStateTraits + Clone + PartialEq,
+{
+}
+
+impl<T> SignalValueTraits for T where T: UserStateTraits + Clone + PartialEq {}
+
+pub trait Signals {
+    /// Starts with the default value at version `0`.
+    #[must_use]
+    fn signal<T>(&mut self, name: impl Into<String>) -> Signal<T>
+    where
+        T: SignalValueTraits;
+}
+
+impl Signals for UiStates {
+    fn signal<T>(&mut self, name: impl Into<String>) -> Signal<T>
+    where
+        T: SignalValueTraits,
+    {
+        let name = name.into();
+        let invalidations = self.invalidations.clone();
+        invalidations.protect(&name);
+        self.get_mut(
+            name,
+            Signal {
+                inner: Arc::new(Mutex::new(SignalState {
+                    value: T::default(),
+                    version: 0,
+                    subscribers: HashMap::new(),
+                    repaint: None,
+                    invalidates: HashSet::new(),
+                })),
+                invalidations,
+            },
+        )
+        .clone()
+    }
+}
+
+type Subscriber<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Handle to a shared value, all clones refer to the same value.
+pub struct Signal<T>
+where
+    T: SignalValueTraits,
+{
+    inner: Arc<Mutex<SignalState<T>>>,
+    invalidations: Invalidations,
+}
+
+impl<T> Clone for Signal<T>
+where
+    T: SignalValueTraits,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            invalidations: self.invalidations.clone(),
+        }
+    }
+}
+
+impl<T> Signal<T>
+where
+    T: SignalValueTraits,
+{
+    pub fn get(&self) -> T {
+        self.lock().value.clone()
+    }
+    /// Reads the value without cloning it.
+    pub fn with<R>(&self, read: impl FnOnce(&T) -> R) -> R {
+        read(&self.lock().value)
+    }
+    /// Counts the changes of the value.
+    pub fn version(&self) -> u64 {
+        self.lock().version
+    }
+    /// `true` if the value changed since `seen` was updated the last time,
+    /// sets it to the current version.
+    pub fn changed_since(&self, seen: &mut u64) -> bool {
+        let version = self.version();
+        let changed = *seen != version;
+        *seen = version;
+        changed
+    }
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+    /// Changes the value in place, subscribers are only notified if it ended
+    /// up different.
+    pub fn update(&self, update: impl FnOnce(&mut T)) {
+        let (value, mut subscribers) = {
+            let mut state = self.lock();
+            let previous = state.value.clone();
+            update(&mut state.value);
+            if state.value == previous {
+                return;
+            }
+            state.version += 1;
+            if let Some(ctx) = &state.repaint {
+                ctx.request_repaint();
+            }
+            self.invalidations.extend(state.invalidates.iter().cloned());
+            (state.value.clone(), std::mem::take(&mut state.subscribers))
+        };
+        // called without the lock, so subscribers can use the signal
+        for subscriber in subscribers.values_mut() {
+            subscriber(&value);
+        }
+        let mut state = self.lock();
+        for (key, subscriber) in subscribers {
+            state.subscribers.entry(key).or_insert(subscriber);
+        }
+    }
+    /// Calls `on_change` with the new value whenever it changes. Subscribing
+    /// again with the same key replaces the previous subscriber, so this can
+    /// be called every frame.
+    pub fn subscribe(&self, key: impl Into<String>, on_change: impl FnMut(&T) + Send + 'static) {
+        self.lock()
+            .subscribers
+            .insert(key.into(), Box::new(on_change));
+    }
+    pub fn unsubscribe(&self, key: &str) {
+        self.lock().subscribers.remove(key);
+    }
+    /// Requests a repaint whenever the value changes, for changes made from
+    /// other threads.
+    pub fn repaint_on_change(&self, ctx: &Context) {
+        self.lock().repaint = Some(ctx.clone());
+    }
+    /// Drops the state of that name from the [`UiStates`] whenever the value
+    /// changes, so cached futures and computed values start over the next
+    /// time they are used.
+    ///
+    /// Signals are never dropped, their handles would stop sharing the value
+    /// with the [`UiStates`]. Names of signals are skipped.
+    pub fn invalidate_on_change(&self, name: impl Into<String>) {
+        self.lock().invalidates.insert(name.into());
+    }
+    fn lock(&self) -> MutexGuard<'_, SignalState<T>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct SignalState<T>
+where
+    T: SignalValueTraits,
+{
+    value: T,
+    version: u64,
+    subscribers: HashMap<String, Subscriber<T>>,
+    repaint: Option<Context>,
+    /// Names of the states dropped on changes.
+    invalidates: HashSet<String>,
+}
+
+/// Names of the states to drop, queued by signals and applied by the
+/// [`UiStates`] before the next access to any state.
+#[derive(Clone, Default)]
+pub(crate) struct Invalidations {
+    queue: Arc<Mutex<InvalidationQueue>>,
+}
+
+#[derive(Default)]
+struct InvalidationQueue {
+    names: Vec<String>,
+    /// Names of the signals, which are never dropped.
+    signals: HashSet<String>,
+}
+
+impl Invalidations {
+    fn protect(&self, signal: &str) {
+        if let Ok(mut queue) = self.queue.lock() {
+            if !queue.signals.contains(signal) {
+                queue.signals.insert(signal.to_string());
+            }
+        }
+    }
+    fn extend(&self, names: impl IntoIterator<Item = String>) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.names.extend(names);
+        }
+    }
+    pub(crate) fn take(&self) -> Vec<String> {
+        self.queue
+            .lock()
+            .map(|mut queue| {
+                let names = std::mem::take(&mut queue.names);
+                names
+                    .into_iter()
+                    .filter(|name| !queue.signals.contains(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,207 @@
+//! Counts up from when it was started, with support for pausing and laps.
+//!
+//! ```
+//! self.ui.stopwatch("stopwatch").default().show(ui);
+//!
+//! // or with a custom ui
+//! self.ui
+//!     .stopwatch("stopwatch")
+//!     .stopwatch_ui(|ui, stopwatch| {
+//!         ui.label(format_elapsed(stopwatch.elapsed()));
+//!         if ui.button("lap").clicked() {
+//!             stopwatch.lap();
+//!         }
+//!     })
+//!     .show(ui);
+//! ```
+
+use chrono::{DateTime, Duration, Local};
+use egui::Ui;
+
+use crate::{
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    UiStates,
+};
+
+pub trait CreateStopwatchUi {
+    fn stopwatch(&mut self, name: impl Into<String>) -> StopwatchBuilder;
+}
+
+impl CreateStopwatchUi for UiStates {
+    fn stopwatch(&mut self, name: impl Into<String>) -> StopwatchBuilder {
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), StopwatchState::default());
+        StopwatchBuilder {
+            stopwatch: Stopwatch { state, now },
+            strings,
+            style,
+            stopwatch_ui: None,
+        }
+    }
+}
+
+// gets the style when shown, so it can be overridden after `default`
+type StopwatchUi = Box<dyn FnOnce(&mut Ui, &mut Stopwatch, &LightStatesStyle)>;
+
+pub struct StopwatchBuilder<'state> {
+    stopwatch: Stopwatch<'state>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    stopwatch_ui: Option<StopwatchUi>,
+}
+
+impl<'state> StopwatchBuilder<'state> {
+    /// Elapsed time, start/stop, lap and reset buttons and the list of laps.
+    #[must_use]
+    pub fn default(mut self) -> Self {
+        let strings = self.strings.clone();
+        self.stopwatch_ui = Some(Box::new(move |ui, stopwatch, style| {
+            let elapsed = format_elapsed(stopwatch.elapsed());
+            style.with_buttons(
+                ui,
+                |ui| {
+                    ui.monospace(elapsed);
+                },
+                |ui| {
+                    if stopwatch.is_running() {
+                        if ui.button(strings.get(TextKey::Stop)).clicked() {
+                            stopwatch.stop();
+                        }
+                        if ui.button(strings.get(TextKey::Lap)).clicked() {
+                            stopwatch.lap();
+                        }
+                    } else {
+                        if ui.button(strings.get(TextKey::Start)).clicked() {
+                            stopwatch.start();
+                        }
+                        if stopwatch.elapsed() > Duration::zero()
+                            && ui.button(strings.get(TextKey::Reset)).clicked()
+                        {
+                            stopwatch.reset();
+                        }
+                    }
+                },
+            );
+            let lap = strings.get(TextKey::Lap);
+            for (index, lap_time) in stopwatch.laps().into_iter().enumerate().rev() {
+                ui.monospace(format!("{lap} {}  {}", index + 1, format_elapsed(lap_time)));
+            }
+        }));
+        self
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    #[must_use]
+    pub fn stopwatch_ui(
+        mut self,
+        stopwatch_ui: impl FnOnce(&mut Ui, &mut Stopwatch) + 'static,
+    ) -> Self {
+        self.stopwatch_ui = Some(Box::new(move |ui, stopwatch, _| {
+            stopwatch_ui(ui, stopwatch)
+        }));
+        self
+    }
+    /// Gives direct access to the stopwatch without showing anything.
+    pub fn stopwatch(&mut self) -> &mut Stopwatch<'state> {
+        &mut self.stopwatch
+    }
+    pub fn show(self, ui: &mut Ui) {
+        let Self {
+            mut stopwatch,
+            style,
+            stopwatch_ui,
+            ..
+        } = self;
+        if let Some(stopwatch_ui) = stopwatch_ui {
+            stopwatch_ui(ui, &mut stopwatch, &style);
+        }
+        if stopwatch.is_running() {
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+/// Controls of a stopwatch, handed to the `stopwatch_ui`. All methods use the
+/// time at which the builder was created.
+pub struct Stopwatch<'state> {
+    state: &'state mut StopwatchState,
+    now: DateTime<Local>,
+}
+
+impl<'state> Stopwatch<'state> {
+    pub fn is_running(&self) -> bool {
+        self.state.started.is_some()
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.state.elapsed
+            + self
+                .state
+                .started
+                .map(|started| self.now - started)
+                .unwrap_or_else(Duration::zero)
+    }
+    /// Starts or resumes the stopwatch.
+    pub fn start(&mut self) {
+        if self.state.started.is_none() {
+            self.state.started = Some(self.now);
+        }
+    }
+    /// Pauses the stopwatch, keeping the elapsed time.
+    pub fn stop(&mut self) {
+        self.state.elapsed = self.elapsed();
+        self.state.started = None;
+    }
+    /// Records a lap at the current elapsed time.
+    pub fn lap(&mut self) {
+        let elapsed = self.elapsed();
+        self.state.lap_splits.push(elapsed);
+    }
+    /// Stops the stopwatch and clears the elapsed time and laps.
+    pub fn reset(&mut self) {
+        *self.state = StopwatchState::default();
+    }
+    /// Duration of every lap, in the order they were recorded.
+    pub fn laps(&self) -> Vec<Duration> {
+        let mut previous = Duration::zero();
+        self.state
+            .lap_splits
+            .iter()
+            .map(|split| {
+                let lap = *split - previous;
+                previous = *split;
+                lap
+            })
+            .collect()
+    }
+    /// Total elapsed time at every lap.
+    pub fn lap_splits(&self) -> &[Duration] {
+        &self.state.lap_splits
+    }
+}
+
+#[derive(Default)]
+struct StopwatchState {
+    /// Set while running, the start of the current run.
+    started: Option<DateTime<Local>>,
+    /// Time of all previous runs.
+    elapsed: Duration,
+    lap_splits: Vec<Duration>,
+}
+
+/// Formats the elapsed time like `01:02.34`, with hours added to the minutes.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let hundredths = elapsed.num_milliseconds().max(0) / 10;
+    format!(
+        "{:02}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
@@ -0,0 +1,201 @@
+//! Texts used by the built-in UIs of this crate.
+//!
+//! Every [`UiStates`][crate::UiStates] holds a [`LightStatesStrings`] which
+//! can be replaced through [`set_strings`][crate::UiStates::set_strings] or
+//! edited through [`strings_mut`][crate::UiStates::strings_mut].
+//!
+//! ```
+//! self.ui.set_strings(LightStatesStrings::default().with_translator(|key| {
+//!     match key {
+//!         "success" => Some("Erfolgreich".to_string()),
+//!         "clear" => Some("Leeren".to_string()),
+//!         _ => None,
+//!     }
+//! }));
+//! ```
+
+use std::sync::Arc;
+
+type Translator = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Identifies one of the texts in [`LightStatesStrings`]. The [`as_str`][TextKey::as_str]
+/// value is what is handed to the translator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextKey {
+    Empty,
+    Success,
+    Error,
+    Reset,
+    Clear,
+    Start,
+    Stop,
+    Lap,
+    Skip,
+    Back,
+    Next,
+    Finish,
+    Submit,
+    Filter,
+    PreviousPage,
+    NextPage,
+    Confirm,
+    ConfirmPrompt,
+    ConfirmQuestion,
+    Cancel,
+    Undo,
+    Close,
+    Retry,
+    Copy,
+    CausedBy,
+    Details,
+}
+
+impl TextKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextKey::Empty => "empty",
+            TextKey::Success => "success",
+            TextKey::Error => "error",
+            TextKey::Reset => "reset",
+            TextKey::Clear => "clear",
+            TextKey::Start => "start",
+            TextKey::Stop => "stop",
+            TextKey::Lap => "lap",
+            TextKey::Skip => "skip",
+            TextKey::Back => "back",
+            TextKey::Next => "next",
+            TextKey::Finish => "finish",
+            TextKey::Submit => "submit",
+            TextKey::Filter => "filter",
+            TextKey::PreviousPage => "previous_page",
+            TextKey::NextPage => "next_page",
+            TextKey::Confirm => "confirm",
+            TextKey::ConfirmPrompt => "confirm_prompt",
+            TextKey::ConfirmQuestion => "confirm_question",
+            TextKey::Cancel => "cancel",
+            TextKey::Undo => "undo",
+            TextKey::Close => "close",
+            TextKey::Retry => "retry",
+            TextKey::Copy => "copy",
+            TextKey::CausedBy => "caused_by",
+            TextKey::Details => "details",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LightStatesStrings {
+    pub empty: String,
+    pub success: String,
+    pub error: String,
+    pub reset: String,
+    pub clear: String,
+    pub start: String,
+    pub stop: String,
+    pub lap: String,
+    pub skip: String,
+    pub back: String,
+    pub next: String,
+    pub finish: String,
+    pub submit: String,
+    pub filter: String,
+    pub previous_page: String,
+    pub next_page: String,
+    pub confirm: String,
+    pub confirm_prompt: String,
+    pub confirm_question: String,
+    pub cancel: String,
+    pub undo: String,
+    pub close: String,
+    pub retry: String,
+    pub copy: String,
+    pub caused_by: String,
+    pub details: String,
+    translator: Option<Translator>,
+}
+
+impl Default for LightStatesStrings {
+    fn default() -> Self {
+        Self {
+            empty: "empty".to_string(),
+            success: "success".to_string(),
+            error: "error".to_string(),
+            reset: "reset".to_string(),
+            clear: "clear".to_string(),
+            start: "start".to_string(),
+            stop: "stop".to_string(),
+            lap: "lap".to_string(),
+            skip: "skip".to_string(),
+            back: "back".to_string(),
+            next: "next".to_string(),
+            finish: "finish".to_string(),
+            submit: "submit".to_string(),
+            filter: "filter".to_string(),
+            previous_page: "‹".to_string(),
+            next_page: "›".to_string(),
+            confirm: "confirm".to_string(),
+            confirm_prompt: "click again to confirm".to_string(),
+            confirm_question: "are you sure?".to_string(),
+            cancel: "cancel".to_string(),
+            undo: "undo".to_string(),
+            close: "close".to_string(),
+            retry: "retry".to_string(),
+            copy: "copy".to_string(),
+            caused_by: "caused by".to_string(),
+            details: "details".to_string(),
+            translator: None,
+        }
+    }
+}
+
+impl LightStatesStrings {
+    /// Sets a lookup that is asked first for every text. Returning `None`
+    /// falls back to the text stored in the field.
+    #[must_use]
+    pub fn with_translator(
+        mut self,
+        translator: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.translator = Some(Arc::new(translator));
+        self
+    }
+
+    pub fn get(&self, key: TextKey) -> String {
+        if let Some(text) = self
+            .translator
+            .as_ref()
+            .and_then(|translator| translator(key.as_str()))
+        {
+            return text;
+        }
+        match key {
+            TextKey::Empty => &self.empty,
+            TextKey::Success => &self.success,
+            TextKey::Error => &self.error,
+            TextKey::Reset => &self.reset,
+            TextKey::Clear => &self.clear,
+            TextKey::Start => &self.start,
+            TextKey::Stop => &self.stop,
+            TextKey::Lap => &self.lap,
+            TextKey::Skip => &self.skip,
+            TextKey::Back => &self.back,
+            TextKey::Next => &self.next,
+            TextKey::Finish => &self.finish,
+            TextKey::Submit => &self.submit,
+            TextKey::Filter => &self.filter,
+            TextKey::PreviousPage => &self.previous_page,
+            TextKey::NextPage => &self.next_page,
+            TextKey::Confirm => &self.confirm,
+            TextKey::ConfirmPrompt => &self.confirm_prompt,
+            TextKey::ConfirmQuestion => &self.confirm_question,
+            TextKey::Cancel => &self.cancel,
+            TextKey::Undo => &self.undo,
+            TextKey::Close => &self.close,
+            TextKey::Retry => &self.retry,
+            TextKey::Copy => &self.copy,
+            TextKey::CausedBy => &self.caused_by,
+            TextKey::Details => &self.details,
+        }
+        .clone()
+    }
+}
@@ -0,0 +1,131 @@
+//! Look of the built-in UIs of this crate.
+//!
+//! Every [`UiStates`][crate::UiStates] holds a [`LightStatesStyle`] which can
+//! be replaced through [`set_style`][crate::UiStates::set_style] or edited
+//! through [`style_mut`][crate::UiStates::style_mut]. The builders using it
+//! also take a `style` to override it for a single call.
+//!
+//! Colors that are not set fall back to the [`egui::Style`] of the [`Ui`]
+//! the widget is shown in.
+//!
+//! ```
+//! self.ui.style_mut().spinner_size = Some(24.);
+//! self.ui
+//!     .future_status::<T>("future_name")
+//!     .style(LightStatesStyle {
+//!         button_placement: ButtonPlacement::Below,
+//!         ..Default::default()
+//!     })
+//!     .default()
+//!     .show(ui);
+//! ```
+
+use egui::{Color32, RichText, Spinner, Ui};
+
+/// Where buttons like "clear" and "reset" are put relative to the status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonPlacement {
+    Before,
+    #[default]
+    After,
+    Below,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightStatesStyle {
+    pub spinner_size: Option<f32>,
+    pub spinner_color: Option<Color32>,
+    /// Fill of progress bars and timer rings.
+    pub progress_color: Option<Color32>,
+    pub success_color: Option<Color32>,
+    pub error_color: Option<Color32>,
+    pub success_icon: String,
+    pub error_icon: String,
+    pub button_placement: ButtonPlacement,
+}
+
+impl Default for LightStatesStyle {
+    fn default() -> Self {
+        Self {
+            spinner_size: None,
+            spinner_color: None,
+            progress_color: None,
+            success_color: None,
+            error_color: None,
+            success_icon: "✔".to_string(),
+            error_icon: "❌".to_string(),
+            button_placement: ButtonPlacement::default(),
+        }
+    }
+}
+
+impl LightStatesStyle {
+    pub fn spinner(&self) -> Spinner {
+        let mut spinner = Spinner::new();
+        if let Some(size) = self.spinner_size {
+            spinner = spinner.size(size);
+        }
+        if let Some(color) = self.spinner_color {
+            spinner = spinner.color(color);
+        }
+        spinner
+    }
+    pub fn success_color(&self, ui: &Ui) -> Color32 {
+        self.success_color
+            .unwrap_or_else(|| ui.visuals().text_color())
+    }
+    pub fn error_color(&self, ui: &Ui) -> Color32 {
+        self.error_color
+            .unwrap_or_else(|| ui.visuals().error_fg_color)
+    }
+    /// Text prefixed with the success icon, in the success color.
+    pub fn success_text(&self, ui: &Ui, text: impl Into<String>) -> RichText {
+        icon_text(&self.success_icon, text.into()).color(self.success_color(ui))
+    }
+    /// Text prefixed with the error icon, in the error color.
+    pub fn error_text(&self, ui: &Ui, text: impl Into<String>) -> RichText {
+        icon_text(&self.error_icon, text.into()).color(self.error_color(ui))
+    }
+    /// Lays out the content and the buttons according to the
+    /// [`button_placement`][LightStatesStyle::button_placement].
+    pub fn with_buttons<R>(
+        &self,
+        ui: &mut Ui,
+        content: impl FnOnce(&mut Ui) -> R,
+        buttons: impl FnOnce(&mut Ui),
+    ) -> R {
+        match self.button_placement {
+            ButtonPlacement::Before => {
+                ui.horizontal(|ui| {
+                    buttons(ui);
+                    content(ui)
+                })
+                .inner
+            }
+            ButtonPlacement::After => {
+                ui.horizontal(|ui| {
+                    let inner = content(ui);
+                    buttons(ui);
+                    inner
+                })
+                .inner
+            }
+            ButtonPlacement::Below => {
+                ui.vertical(|ui| {
+                    let inner = content(ui);
+                    ui.horizontal(buttons);
+                    inner
+                })
+                .inner
+            }
+        }
+    }
+}
+
+fn icon_text(icon: &str, text: String) -> RichText {
+    if icon.is_empty() {
+        RichText::new(text)
+    } else {
+        RichText::new(format!("{icon} {text}"))
+    }
+}
@@ -0,0 +1,309 @@
+//! Sorting, filtering and pagination of tables, either applied to a slice or
+//! handed to the server when fetching a page.
+//!
+//! ```
+//! let mut view = self.ui.table_view_state("users");
+//! view.filter_ui(ui);
+//! ui.horizontal(|ui| {
+//!     view.sort_header(ui, "name", "Name");
+//!     view.sort_header(ui, "age", "Age");
+//! });
+//! let rows = view.apply(
+//!     &self.users,
+//!     |user, filter| user.name.contains(filter),
+//!     |a, b, column| match column {
+//!         "age" => a.age.cmp(&b.age),
+//!         _ => a.name.cmp(&b.name),
+//!     },
+//! );
+//! for user in rows {
+//!     ui.label(&user.name);
+//! }
+//! view.pagination_ui(ui);
+//! ```
+//!
+//! For server side paging the page is fetched every time the query changes:
+//!
+//! ```
+//! let client = self.client.clone();
+//! self.ui
+//!     .table_page("users", move |query| ImmediateValuePromise::new(client.users(query.clone())))
+//!     .done_ui(|ui, result, _| {
+//!         // show the rows
+//!     })
+//!     .show(ui);
+//! ```
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Duration, Local};
+use egui::{Button, Response, TextEdit, Ui};
+use lazy_async_promise::ImmediateValuePromise;
+
+use crate::{
+    future_await::{FutureAwait, FutureStatusBuilder},
+    rate_limit::DebounceState,
+    strings::{LightStatesStrings, TextKey},
+    UiStates,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 25;
+const DEFAULT_FILTER_DELAY_MILLIS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Everything needed to produce the rows of the current page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableQuery {
+    pub sort_column: Option<String>,
+    pub sort_direction: SortDirection,
+    /// Filter text once it stopped changing.
+    pub filter: String,
+    /// Starts at `0`.
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl Default for TableQuery {
+    fn default() -> Self {
+        Self {
+            sort_column: None,
+            sort_direction: SortDirection::default(),
+            filter: String::new(),
+            page: 0,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+pub trait TableViews {
+    #[must_use]
+    fn table_view_state(&mut self, name: impl Into<String>) -> TableView;
+
+    /// Fetches the current page of the table view of that name whenever its
+    /// query changes and returns the status of the fetch. The future is
+    /// stored under `<name>_page`, so it can also be used with the other
+    /// [`FutureAwait`] functions.
+    #[must_use]
+    fn table_page<T>(
+        &mut self,
+        name: impl Into<String>,
+        fetch: impl FnOnce(&TableQuery) -> ImmediateValuePromise<T>,
+    ) -> FutureStatusBuilder<T>
+    where
+        T: Send + 'static;
+}
+
+impl TableViews for UiStates {
+    fn table_view_state(&mut self, name: impl Into<String>) -> TableView {
+        let strings = self.strings.clone();
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), TableViewState::default());
+        TableView {
+            state,
+            now,
+            strings,
+        }
+    }
+
+    fn table_page<T>(
+        &mut self,
+        name: impl Into<String>,
+        fetch: impl FnOnce(&TableQuery) -> ImmediateValuePromise<T>,
+    ) -> FutureStatusBuilder<T>
+    where
+        T: Send + 'static,
+    {
+        let name = name.into();
+        let page_name = format!("{name}_page");
+        let state = self.get_mut(name, TableViewState::default());
+        if state.fetched.as_ref() != Some(&state.query) {
+            let query = state.query.clone();
+            state.fetched = Some(query.clone());
+            self.set_future::<T>(page_name.clone()).set(fetch(&query));
+        }
+        self.future_status::<T>(page_name)
+    }
+}
+
+/// Query of a table view and the UIs to change it.
+pub struct TableView<'state> {
+    state: &'state mut TableViewState,
+    now: DateTime<Local>,
+    strings: LightStatesStrings,
+}
+
+impl<'state> TableView<'state> {
+    pub fn query(&self) -> &TableQuery {
+        &self.state.query
+    }
+    /// Sorts by the column, toggling the direction if it is already sorted
+    /// by it.
+    pub fn sort_by(&mut self, column: impl Into<String>) {
+        let column = column.into();
+        let query = &mut self.state.query;
+        if query.sort_column.as_ref() == Some(&column) {
+            query.sort_direction = match query.sort_direction {
+                SortDirection::Ascending => SortDirection::Descending,
+                SortDirection::Descending => SortDirection::Ascending,
+            };
+        } else {
+            query.sort_column = Some(column);
+            query.sort_direction = SortDirection::Ascending;
+        }
+        query.page = 0;
+    }
+    /// Header button sorting by the column when clicked, showing the
+    /// direction if it is sorted by it.
+    pub fn sort_header(
+        &mut self,
+        ui: &mut Ui,
+        column: impl Into<String>,
+        label: impl Into<String>,
+    ) -> Response {
+        let column = column.into();
+        let mut label = label.into();
+        let query = &self.state.query;
+        if query.sort_column.as_ref() == Some(&column) {
+            label.push_str(match query.sort_direction {
+                SortDirection::Ascending => " ⬆",
+                SortDirection::Descending => " ⬇",
+            });
+        }
+        let response = ui.add(Button::new(label).frame(false));
+        if response.clicked() {
+            self.sort_by(column);
+        }
+        response
+    }
+    /// Applies the filter right away, going back to the first page.
+    pub fn set_filter(&mut self, filter: impl Into<String>) {
+        let filter = filter.into();
+        self.state.filter_input.clone_from(&filter);
+        self.state.filter_debounce.settle(filter.clone());
+        if self.state.query.filter != filter {
+            self.state.query.filter = filter;
+            self.state.query.page = 0;
+        }
+    }
+    /// How long the filter text has to stay unchanged before it is applied,
+    /// defaults to 300 milliseconds.
+    pub fn set_filter_delay(&mut self, delay: Duration) {
+        self.state.filter_debounce.set_delay(delay);
+    }
+    /// Text edit for the filter, applied once it stops changing.
+    pub fn filter_ui(&mut self, ui: &mut Ui) -> Response {
+        let response = ui.add(
+            TextEdit::singleline(&mut self.state.filter_input)
+                .hint_text(self.strings.get(TextKey::Filter)),
+        );
+        let input = self.state.filter_input.clone();
+        if let Some(filter) = self.state.filter_debounce.update(ui.ctx(), self.now, input) {
+            self.set_filter(filter);
+        }
+        response
+    }
+    /// Clamped to the last page once the total is known.
+    pub fn set_page(&mut self, page: usize) {
+        self.state.query.page = match self.page_count() {
+            Some(count) => page.min(count.saturating_sub(1)),
+            None => page,
+        };
+    }
+    /// Goes back to the first page.
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.state.query.page_size = page_size.max(1);
+        self.state.query.page = 0;
+    }
+    /// Total number of rows after filtering, set by [`apply`][TableView::apply]
+    /// or by hand for server side paging.
+    pub fn set_total(&mut self, total: usize) {
+        self.state.total = Some(total);
+    }
+    pub fn page_count(&self) -> Option<usize> {
+        self.state
+            .total
+            .map(|total| total.div_ceil(self.state.query.page_size).max(1))
+    }
+    /// Previous and next buttons around the current page.
+    pub fn pagination_ui(&mut self, ui: &mut Ui) {
+        let page = self.state.query.page;
+        let count = self.page_count();
+        let previous = self.strings.get(TextKey::PreviousPage);
+        let next = self.strings.get(TextKey::NextPage);
+        ui.horizontal(|ui| {
+            if ui.add_enabled(page > 0, Button::new(previous)).clicked() {
+                self.set_page(page - 1);
+            }
+            match count {
+                Some(count) => ui.label(format!("{} / {}", page + 1, count)),
+                None => ui.label((page + 1).to_string()),
+            };
+            let has_next = !matches!(count, Some(count) if page + 1 >= count);
+            if ui.add_enabled(has_next, Button::new(next)).clicked() {
+                self.set_page(page + 1);
+            }
+        });
+    }
+    /// Filters, sorts and pages the rows, returning the rows of the current
+    /// page. `matches` gets the filter text, `compare` the sort column.
+    pub fn apply<'rows, T>(
+        &mut self,
+        rows: &'rows [T],
+        matches: impl Fn(&T, &str) -> bool,
+        compare: impl Fn(&T, &T, &str) -> Ordering,
+    ) -> Vec<&'rows T> {
+        let query = &self.state.query;
+        let mut rows: Vec<&T> = rows
+            .iter()
+            .filter(|row| query.filter.is_empty() || matches(row, &query.filter))
+            .collect();
+        if let Some(column) = &query.sort_column {
+            rows.sort_by(|a, b| {
+                let ordering = compare(a, b, column);
+                match query.sort_direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+        self.set_total(rows.len());
+        self.set_page(self.state.query.page);
+
+        let page_size = self.state.query.page_size;
+        rows.into_iter()
+            .skip(self.state.query.page * page_size)
+            .take(page_size)
+            .collect()
+    }
+}
+
+struct TableViewState {
+    query: TableQuery,
+    /// Text of the filter edit, applied to the query by the timer.
+    filter_input: String,
+    /// Applies the filter input once it stopped changing.
+    filter_debounce: DebounceState<String>,
+    total: Option<usize>,
+    /// Query of the last page fetched through [`TableViews::table_page`].
+    fetched: Option<TableQuery>,
+}
+
+impl Default for TableViewState {
+    fn default() -> Self {
+        Self {
+            query: TableQuery::default(),
+            filter_input: String::new(),
+            filter_debounce: DebounceState::from(Duration::milliseconds(
+                DEFAULT_FILTER_DELAY_MILLIS,
+            )),
+            total: None,
+            fetched: None,
+        }
+    }
+}
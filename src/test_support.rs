@@ -0,0 +1,40 @@
+//! Helpers for driving the states in tests without a window or real time.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Local};
+use egui::{CentralPanel, Context, RawInput, Ui};
+
+use crate::{clock::Clock, UiStates};
+
+/// Clock that only moves when told to, shared by all of its clones.
+#[derive(Clone)]
+pub(crate) struct ManualClock(Arc<Mutex<DateTime<Local>>>);
+
+impl ManualClock {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(Local::now())))
+    }
+    pub(crate) fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+    /// Empty states driven by this clock.
+    pub(crate) fn states(&self) -> UiStates {
+        let mut states = UiStates::default();
+        states.set_clock(self.clone());
+        states
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// Runs a single frame, showing `add_contents` in a central panel.
+pub(crate) fn run_ui(ctx: &Context, mut add_contents: impl FnMut(&mut Ui)) {
+    let _ = ctx.run(RawInput::default(), |ctx| {
+        CentralPanel::default().show(ctx, |ui| add_contents(ui));
+    });
+}
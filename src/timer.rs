@@ -1,9 +1,27 @@
-use chrono::{DateTime, Duration, Local};
-use egui::Ui;
+use std::any::Any;
 
-use crate::{UiStates, UserStateTraits};
+use chrono::{DateTime, Duration, FixedOffset, Local, TimeZone};
+use egui::{ProgressBar, Ui};
+
+use crate::{
+    clock::SharedClock,
+    events::StateEvent,
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    timer_widgets::{CountdownFormat, TimerRing},
+    UiStates, UserStateTraits,
+};
 
 pub trait CreateTimerUi {
+    /// If only built-in UIs are used, the UI types can not be inferred and
+    /// have to be given as [`DefaultDoneUi`] and [`DefaultTimingUi`]:
+    ///
+    /// ```
+    /// self.ui
+    ///     .timer::<DefaultDoneUi<()>, DefaultTimingUi<()>, ()>("timer".into(), 30)
+    ///     .progress_bar()
+    ///     .show(ui);
+    /// ```
     fn timer<'state, DoneUi, TimingUi, UserState>(
         &'state mut self,
         name: String,
@@ -13,6 +31,25 @@ pub trait CreateTimerUi {
         DoneUi: DoneUiTraits<UserState>,
         TimingUi: TimingUiTraits<UserState>,
         UserState: UserStateTraits;
+
+    /// Timer running until a point in time instead of for a duration. Once
+    /// the deadline has passed a [`StateEvent::TimerExpired`] is queued, even
+    /// if the timer is not shown, as long as [`UiStates::poll`] or
+    /// [`UiStates::drain_events`] is called.
+    ///
+    /// ```
+    /// let expires = Utc.with_ymd_and_hms(2024, 11, 5, 14, 30, 0).unwrap();
+    /// self.ui
+    ///     .timer_at("session", expires)
+    ///     .countdown_text(CountdownFormat::HoursMinutesSeconds)
+    ///     .expired_ui(|ui| {
+    ///         ui.label("session expired");
+    ///     })
+    ///     .show(ui);
+    /// ```
+    fn timer_at<Tz>(&mut self, name: impl Into<String>, deadline: DateTime<Tz>) -> DeadlineBuilder
+    where
+        Tz: TimeZone;
 }
 
 impl CreateTimerUi for UiStates {
@@ -26,7 +63,9 @@ impl CreateTimerUi for UiStates {
         TimingUi: TimingUiTraits<UserState>,
         UserState: UserStateTraits,
     {
-
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let clock = self.clock.clone();
         let CompleteTimerState::<UserState> {
             internal_state,
             user_state,
@@ -34,12 +73,91 @@ impl CreateTimerUi for UiStates {
         TimerBuilder {
             internal_state,
             user_state,
+            strings,
+            style,
+            clock,
             timer_done_ui: None,
             timer_timing_ui: None,
+            builtin_timing_ui: BuiltinTimingUi::default(),
         }
     }
+
+    fn timer_at<Tz>(&mut self, name: impl Into<String>, deadline: DateTime<Tz>) -> DeadlineBuilder
+    where
+        Tz: TimeZone,
+    {
+        let name = name.into();
+        let now = self.clock.now();
+        let deadline = deadline.fixed_offset();
+        self.register_poller(name.clone(), poll_deadline_state);
+        let (state, events) = self.get_mut_with_events(name.clone(), DeadlineState::from(deadline));
+        if state.deadline != deadline {
+            *state = DeadlineState::from(deadline);
+        }
+        state.poll(&name, now, events);
+        DeadlineBuilder {
+            state,
+            now,
+            waiting_ui: None,
+            expired_ui: None,
+        }
+    }
+}
+
+/// Done UI type to give to [`CreateTimerUi::timer`] when only the built-in
+/// UIs are used.
+pub type DefaultDoneUi<State> = fn(&mut Ui, &mut State, &mut dyn FnMut());
+/// Timing UI type to give to [`CreateTimerUi::timer`] when only the built-in
+/// UIs are used.
+pub type DefaultTimingUi<State> = fn(&mut Ui, &mut State, f32);
+
+/// Timing UIs that come with the crate, drawn with the style of the builder
+/// when shown.
+#[derive(Clone, Copy, Default)]
+enum BuiltinTimingUi {
+    #[default]
+    Spinner,
+    ProgressBar,
+    Ring,
+    Countdown(CountdownFormat),
 }
 
+impl BuiltinTimingUi {
+    fn show(self, ui: &mut Ui, style: &LightStatesStyle, duration: Duration, progress: f32) {
+        match self {
+            BuiltinTimingUi::Spinner => {
+                ui.add(style.spinner());
+            }
+            BuiltinTimingUi::ProgressBar => {
+                let mut bar = ProgressBar::new(progress);
+                if let Some(color) = style.progress_color {
+                    bar = bar.fill(color);
+                }
+                ui.add(bar);
+                ui.ctx().request_repaint();
+            }
+            BuiltinTimingUi::Ring => {
+                let mut ring = TimerRing::new(1. - progress)
+                    .text(CountdownFormat::Seconds.format(remaining(duration, progress)));
+                if let Some(size) = style.spinner_size {
+                    ring = ring.size(size);
+                }
+                if let Some(color) = style.progress_color {
+                    ring = ring.color(color);
+                }
+                ui.add(ring);
+                ui.ctx().request_repaint();
+            }
+            BuiltinTimingUi::Countdown(format) => {
+                ui.label(format.format(remaining(duration, progress)));
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+}
+
+/// Parts without a UI of their own are shown with the
+/// [`default`][TimerBuilder::default] UI.
 pub struct TimerBuilder<'state, DoneUi, TimingUi, UserState>
 where
     DoneUi: DoneUiTraits<UserState>,
@@ -48,8 +166,13 @@ where
 {
     internal_state: &'state mut TimerState,
     user_state: &'state mut UserState,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    clock: SharedClock,
     timer_done_ui: Option<DoneUi>,
     timer_timing_ui: Option<TimingUi>,
+    /// Used while no `timer_timing_ui` is set.
+    builtin_timing_ui: BuiltinTimingUi,
 }
 
 impl<'state, DoneUi, TimingUi, State> TimerBuilder<'state, DoneUi, TimingUi, State>
@@ -58,6 +181,36 @@ where
     TimingUi: TimingUiTraits<State>,
     State: UserStateTraits,
 {
+    /// Start button while the timer is not running and a spinner while it is.
+    #[must_use]
+    pub fn default(mut self) -> Self {
+        self.timer_done_ui = None;
+        self.timer_timing_ui = None;
+        self.builtin_timing_ui = BuiltinTimingUi::Spinner;
+        self
+    }
+    /// Shows a [`ProgressBar`] filling up while the timer runs.
+    #[must_use]
+    pub fn progress_bar(self) -> Self {
+        self.builtin_timing_ui(BuiltinTimingUi::ProgressBar)
+    }
+    /// Shows a [`TimerRing`] emptying while the timer runs, with the remaining
+    /// time in the middle.
+    #[must_use]
+    pub fn ring(self) -> Self {
+        self.builtin_timing_ui(BuiltinTimingUi::Ring)
+    }
+    /// Shows the remaining time as text, like `00:42`.
+    #[must_use]
+    pub fn countdown_text(self, format: CountdownFormat) -> Self {
+        self.builtin_timing_ui(BuiltinTimingUi::Countdown(format))
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
     pub fn timer_done_ui(mut self, ui: DoneUi) -> Self {
         self.timer_done_ui = Some(ui);
         self
@@ -66,16 +219,22 @@ where
         self.timer_timing_ui = Some(ui);
         self
     }
+    fn builtin_timing_ui(mut self, builtin: BuiltinTimingUi) -> Self {
+        self.timer_timing_ui = None;
+        self.builtin_timing_ui = builtin;
+        self
+    }
     pub fn show(self, ui: &mut Ui) {
         let TimerBuilder {
             internal_state,
             user_state,
-            timer_done_ui: Some(timer_done_ui),
-            timer_timing_ui: Some(timer_timing_ui),
-        } = self
-        else {
-            unreachable!()
-        };
+            strings,
+            style,
+            clock,
+            timer_done_ui,
+            timer_timing_ui,
+            builtin_timing_ui,
+        } = self;
         let TimerState {
             timer_started,
             timer_duration,
@@ -83,18 +242,35 @@ where
         match timer_started {
             None => {
                 let mut reset_timer = || {
-                    let _ = timer_started.insert(Local::now());
+                    let _ = timer_started.insert(clock.now());
                 };
-                timer_done_ui(ui, user_state, &mut reset_timer);
+                match timer_done_ui {
+                    Some(timer_done_ui) => timer_done_ui(ui, user_state, &mut reset_timer),
+                    None => {
+                        if ui.button(strings.get(TextKey::Start)).clicked() {
+                            reset_timer();
+                        }
+                    }
+                }
             }
             Some(start_time) => {
-                let now = Local::now();
+                let now = clock.now();
                 let passed_time = now - *start_time;
 
                 let percentage_passed = passed_time.num_milliseconds() as f64
                     / timer_duration.num_milliseconds() as f64;
 
-                timer_timing_ui(ui, user_state, percentage_passed as f32);
+                match timer_timing_ui {
+                    Some(timer_timing_ui) => {
+                        timer_timing_ui(ui, user_state, percentage_passed as f32)
+                    }
+                    None => builtin_timing_ui.show(
+                        ui,
+                        &style,
+                        *timer_duration,
+                        percentage_passed as f32,
+                    ),
+                }
 
                 if passed_time >= *timer_duration {
                     let _ = timer_started.take();
@@ -104,6 +280,105 @@ where
     }
 }
 
+pub struct DeadlineBuilder<'state> {
+    state: &'state mut DeadlineState,
+    now: DateTime<Local>,
+    waiting_ui: Option<Box<dyn FnOnce(&mut Ui, Duration)>>,
+    expired_ui: Option<Box<dyn FnOnce(&mut Ui)>>,
+}
+
+impl<'state> DeadlineBuilder<'state> {
+    /// The deadline, in the time zone it was passed in with.
+    pub fn deadline(&self) -> DateTime<FixedOffset> {
+        self.state.deadline
+    }
+    pub fn remaining(&self) -> Duration {
+        self.state
+            .deadline
+            .signed_duration_since(self.now)
+            .max(Duration::zero())
+    }
+    pub fn is_expired(&self) -> bool {
+        self.state.expired
+    }
+    /// Gets the remaining time, shown until the deadline has passed.
+    #[must_use]
+    pub fn waiting_ui(mut self, waiting_ui: impl FnOnce(&mut Ui, Duration) + 'static) -> Self {
+        self.waiting_ui = Some(Box::new(waiting_ui));
+        self
+    }
+    #[must_use]
+    pub fn expired_ui(mut self, expired_ui: impl FnOnce(&mut Ui) + 'static) -> Self {
+        self.expired_ui = Some(Box::new(expired_ui));
+        self
+    }
+    /// Shows the remaining time as text, like `00:42`.
+    #[must_use]
+    pub fn countdown_text(self, format: CountdownFormat) -> Self {
+        self.waiting_ui(move |ui, remaining| {
+            ui.label(format.format(remaining));
+        })
+    }
+    pub fn show(self, ui: &mut Ui) {
+        if self.state.expired {
+            if let Some(expired_ui) = self.expired_ui {
+                expired_ui(ui);
+            }
+            return;
+        }
+        let remaining = self.remaining();
+        if let Some(waiting_ui) = self.waiting_ui {
+            waiting_ui(ui, remaining);
+        }
+        // repaint when the seconds change, or when the deadline passes
+        ui.ctx()
+            .request_repaint_after(TimerState::until_next_second(remaining));
+    }
+}
+
+struct DeadlineState {
+    deadline: DateTime<FixedOffset>,
+    /// Set once the deadline was seen as passed, so the event is only sent
+    /// once.
+    expired: bool,
+}
+
+impl From<DateTime<FixedOffset>> for DeadlineState {
+    fn from(deadline: DateTime<FixedOffset>) -> Self {
+        Self {
+            deadline,
+            expired: false,
+        }
+    }
+}
+
+impl DeadlineState {
+    fn poll(&mut self, name: &str, now: DateTime<Local>, events: &mut Vec<StateEvent>) {
+        if !self.expired && now >= self.deadline {
+            self.expired = true;
+            events.push(StateEvent::TimerExpired {
+                name: name.to_string(),
+            });
+        }
+    }
+}
+
+fn poll_deadline_state(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    clock: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) {
+    if let Some(state) = state.downcast_mut::<DeadlineState>() {
+        state.poll(name, clock.now(), events);
+    }
+}
+
+fn remaining(duration: Duration, progress: f32) -> Duration {
+    let millis = duration.num_milliseconds() as f64 * (1. - progress as f64);
+    Duration::milliseconds(millis.max(0.) as i64)
+}
+
 struct CompleteTimerState<UserState>
 where
     UserState: UserStateTraits
@@ -135,6 +410,60 @@ impl From<i64> for TimerState {
     }
 }
 
+impl From<Duration> for TimerState {
+    fn from(value: Duration) -> Self {
+        Self {
+            timer_started: None,
+            timer_duration: value,
+        }
+    }
+}
+
+impl TimerState {
+    pub(crate) fn start(&mut self, now: DateTime<Local>) {
+        self.timer_started = Some(now);
+    }
+    pub(crate) fn stop(&mut self) {
+        self.timer_started = None;
+    }
+    pub(crate) fn is_running(&self) -> bool {
+        self.timer_started.is_some()
+    }
+    pub(crate) fn set_duration(&mut self, duration: Duration) {
+        self.timer_duration = duration;
+    }
+    pub(crate) fn duration(&self) -> Duration {
+        self.timer_duration
+    }
+    /// Point in time at which the timer is done, `None` if it is not running.
+    pub(crate) fn end(&self) -> Option<DateTime<Local>> {
+        self.timer_started
+            .map(|started| started + self.timer_duration)
+    }
+    /// Time left until the timer is done, `None` if it is not running.
+    pub(crate) fn remaining(&self, now: DateTime<Local>) -> Option<Duration> {
+        self.timer_started
+            .map(|started| (started + self.timer_duration - now).max(Duration::zero()))
+    }
+    /// Delay until a countdown showing whole seconds of `remaining` changes,
+    /// for [`Context::request_repaint_after`][egui::Context::request_repaint_after].
+    pub(crate) fn until_next_second(remaining: Duration) -> std::time::Duration {
+        Duration::milliseconds(remaining.num_milliseconds() % 1000 + 1)
+            .to_std()
+            .unwrap_or_default()
+    }
+    /// Stops the timer if its duration has passed, returns `true` only on the
+    /// call that stopped it.
+    pub(crate) fn finish_if_elapsed(&mut self, now: DateTime<Local>) -> bool {
+        if self.remaining(now) == Some(Duration::zero()) {
+            self.stop();
+            true
+        } else {
+            false
+        }
+    }
+}
+
 pub trait DoneUiTraits<State>
 where
     Self: FnOnce(&mut Ui, &mut State, &mut dyn FnMut()) + 'static,
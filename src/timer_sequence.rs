@@ -0,0 +1,330 @@
+//! Timer walking through a list of named phases, each with its own duration.
+//!
+//! ```
+//! self.ui
+//!     .timer_sequence(
+//!         "pomodoro",
+//!         vec![
+//!             Phase::new("work", Duration::minutes(25)),
+//!             Phase::new("break", Duration::minutes(5)),
+//!         ],
+//!     )
+//!     .looping(true)
+//!     .timing_ui(|ui, sequence| {
+//!         if let Some(phase) = sequence.phase() {
+//!             ui.label(&phase.name);
+//!         }
+//!         ui.add(ProgressBar::new(sequence.phase_progress()));
+//!         ui.add(ProgressBar::new(sequence.overall_progress()));
+//!         if ui.button("skip").clicked() {
+//!             sequence.skip();
+//!         }
+//!     })
+//!     .show(ui);
+//! ```
+
+use chrono::{DateTime, Duration, Local};
+use egui::{ProgressBar, Ui};
+
+use crate::{
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    timer::TimerState,
+    timer_widgets::CountdownFormat,
+    UiStates,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub name: String,
+    pub duration: Duration,
+}
+
+impl Phase {
+    pub fn new(name: impl Into<String>, duration: Duration) -> Self {
+        Self {
+            name: name.into(),
+            duration,
+        }
+    }
+}
+
+pub trait CreateTimerSequenceUi {
+    /// If the phases differ from the ones stored under this name, the
+    /// sequence is reset to the new phases.
+    fn timer_sequence(
+        &mut self,
+        name: impl Into<String>,
+        phases: Vec<Phase>,
+    ) -> TimerSequenceBuilder;
+}
+
+impl CreateTimerSequenceUi for UiStates {
+    fn timer_sequence(
+        &mut self,
+        name: impl Into<String>,
+        phases: Vec<Phase>,
+    ) -> TimerSequenceBuilder {
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), TimerSequenceState::default());
+        if state.phases != phases {
+            *state = TimerSequenceState {
+                phases,
+                ..Default::default()
+            };
+        }
+        TimerSequenceBuilder {
+            sequence: TimerSequence { state, now },
+            strings,
+            style,
+            done_ui: None,
+            timing_ui: None,
+        }
+    }
+}
+
+// gets the style when shown, so it can be overridden after `default`
+type SequenceUi = Box<dyn FnOnce(&mut Ui, &mut TimerSequence, &LightStatesStyle)>;
+
+pub struct TimerSequenceBuilder<'state> {
+    sequence: TimerSequence<'state>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    done_ui: Option<SequenceUi>,
+    timing_ui: Option<SequenceUi>,
+}
+
+impl<'state> TimerSequenceBuilder<'state> {
+    /// Start button while not running. While running the current phase with
+    /// its remaining time, a progress bar and back, skip and reset buttons.
+    #[must_use]
+    pub fn default(mut self) -> Self {
+        let done_strings = self.strings.clone();
+        let strings = self.strings.clone();
+        self.timing_ui = Some(Box::new(move |ui, sequence, style| {
+            let text = match sequence.phase() {
+                Some(phase) => format!(
+                    "{} {}",
+                    phase.name,
+                    CountdownFormat::default().format(sequence.phase_remaining())
+                ),
+                None => String::new(),
+            };
+            let progress = sequence.phase_progress();
+            style.with_buttons(
+                ui,
+                |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(text);
+                        let mut bar = ProgressBar::new(progress);
+                        if let Some(color) = style.progress_color {
+                            bar = bar.fill(color);
+                        }
+                        ui.add(bar);
+                    });
+                },
+                |ui| {
+                    if ui.button(strings.get(TextKey::Back)).clicked() {
+                        sequence.back();
+                    }
+                    if ui.button(strings.get(TextKey::Skip)).clicked() {
+                        sequence.skip();
+                    }
+                    if ui.button(strings.get(TextKey::Reset)).clicked() {
+                        sequence.reset();
+                    }
+                },
+            );
+        }));
+        self.done_ui(move |ui, sequence| {
+            if ui.button(done_strings.get(TextKey::Start)).clicked() {
+                sequence.start();
+            }
+        })
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Restart at the first phase after the last one instead of stopping.
+    #[must_use]
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.sequence.state.looping = looping;
+        self
+    }
+    /// Shown while the sequence is not running.
+    #[must_use]
+    pub fn done_ui(mut self, done_ui: impl FnOnce(&mut Ui, &mut TimerSequence) + 'static) -> Self {
+        self.done_ui = Some(Box::new(move |ui, sequence, _| done_ui(ui, sequence)));
+        self
+    }
+    /// Shown while the sequence is running.
+    #[must_use]
+    pub fn timing_ui(
+        mut self,
+        timing_ui: impl FnOnce(&mut Ui, &mut TimerSequence) + 'static,
+    ) -> Self {
+        self.timing_ui = Some(Box::new(move |ui, sequence, _| timing_ui(ui, sequence)));
+        self
+    }
+    pub fn show(self, ui: &mut Ui) {
+        let Self {
+            mut sequence,
+            style,
+            done_ui,
+            timing_ui,
+            ..
+        } = self;
+        sequence.state.advance(sequence.now);
+        if sequence.is_running() {
+            if let Some(timing_ui) = timing_ui {
+                timing_ui(ui, &mut sequence, &style);
+            }
+            ui.ctx().request_repaint();
+        } else if let Some(done_ui) = done_ui {
+            done_ui(ui, &mut sequence, &style);
+        }
+    }
+}
+
+/// Progress and controls of a timer sequence, handed to its UIs. All methods
+/// use the time at which the builder was created.
+pub struct TimerSequence<'state> {
+    state: &'state mut TimerSequenceState,
+    now: DateTime<Local>,
+}
+
+impl<'state> TimerSequence<'state> {
+    pub fn is_running(&self) -> bool {
+        self.state.timer.is_running()
+    }
+    pub fn phases(&self) -> &[Phase] {
+        &self.state.phases
+    }
+    pub fn phase_index(&self) -> usize {
+        self.state.current
+    }
+    pub fn phase(&self) -> Option<&Phase> {
+        self.state.phases.get(self.state.current)
+    }
+    /// Time left in the current phase, the full phase if not running.
+    pub fn phase_remaining(&self) -> Duration {
+        self.state
+            .timer
+            .remaining(self.now)
+            .or_else(|| self.phase().map(|phase| phase.duration))
+            .unwrap_or_else(Duration::zero)
+    }
+    /// Progress of the current phase from `0.0` to `1.0`.
+    pub fn phase_progress(&self) -> f32 {
+        let Some(phase) = self.phase() else {
+            return 0.;
+        };
+        fraction(phase.duration - self.phase_remaining(), phase.duration)
+    }
+    /// Progress through all phases from `0.0` to `1.0`.
+    pub fn overall_progress(&self) -> f32 {
+        let Some(phase) = self.phase() else {
+            return 0.;
+        };
+        let before = self.state.phases[..self.state.current]
+            .iter()
+            .fold(Duration::zero(), |sum, phase| sum + phase.duration);
+        fraction(
+            before + phase.duration - self.phase_remaining(),
+            self.state.total_duration(),
+        )
+    }
+    /// Starts the sequence at the current phase.
+    pub fn start(&mut self) {
+        self.state.start_phase(self.state.current, self.now);
+    }
+    /// Moves on to the next phase, stopping after the last one unless the
+    /// sequence is looping.
+    pub fn skip(&mut self) {
+        if self.state.current + 1 < self.state.phases.len() {
+            self.state.start_phase(self.state.current + 1, self.now);
+        } else if self.state.looping {
+            self.state.start_phase(0, self.now);
+        } else {
+            self.reset();
+        }
+    }
+    /// Goes back to the previous phase, or restarts the first one.
+    pub fn back(&mut self) {
+        let previous = self.state.current.saturating_sub(1);
+        self.state.start_phase(previous, self.now);
+    }
+    /// Stops the sequence and goes back to the first phase.
+    pub fn reset(&mut self) {
+        self.state.current = 0;
+        self.state.timer.stop();
+    }
+}
+
+struct TimerSequenceState {
+    phases: Vec<Phase>,
+    current: usize,
+    looping: bool,
+    /// Runs for the duration of the current phase.
+    timer: TimerState,
+}
+
+impl Default for TimerSequenceState {
+    fn default() -> Self {
+        Self {
+            phases: vec![],
+            current: 0,
+            looping: false,
+            timer: TimerState::from(Duration::zero()),
+        }
+    }
+}
+
+impl TimerSequenceState {
+    fn total_duration(&self) -> Duration {
+        self.phases
+            .iter()
+            .fold(Duration::zero(), |sum, phase| sum + phase.duration)
+    }
+    fn start_phase(&mut self, index: usize, start: DateTime<Local>) {
+        let Some(phase) = self.phases.get(index) else {
+            return;
+        };
+        self.current = index;
+        self.timer.set_duration(phase.duration);
+        self.timer.start(start);
+    }
+    /// Moves through all phases that ended until `now`, each next phase
+    /// starting at the end of the previous one.
+    fn advance(&mut self, now: DateTime<Local>) {
+        if self.total_duration() <= Duration::zero() {
+            self.timer.stop();
+            return;
+        }
+        while let Some(end) = self.timer.end() {
+            if end > now {
+                break;
+            }
+            if self.current + 1 < self.phases.len() {
+                self.start_phase(self.current + 1, end);
+            } else if self.looping {
+                self.start_phase(0, end);
+            } else {
+                self.current = 0;
+                self.timer.stop();
+            }
+        }
+    }
+}
+
+fn fraction(part: Duration, whole: Duration) -> f32 {
+    if whole <= Duration::zero() {
+        return 0.;
+    }
+    (part.num_milliseconds() as f64 / whole.num_milliseconds() as f64).clamp(0., 1.) as f32
+}
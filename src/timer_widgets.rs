@@ -0,0 +1,140 @@
+//! Widgets used by the built-in timing UIs of the [`TimerBuilder`][crate::timer::TimerBuilder].
+//!
+//! They can also be used inside of a custom `timing_ui`:
+//!
+//! ```
+//! self.ui
+//!     .timer::<DefaultDoneUi<()>, _, ()>("timer".into(), 30)
+//!     .timing_ui(|ui, _, progress| {
+//!         ui.add(TimerRing::new(1. - progress));
+//!     })
+//!     .show(ui);
+//! ```
+
+use std::f32::consts::TAU;
+
+use chrono::Duration;
+use egui::{pos2, vec2, Align2, Color32, Response, Sense, Shape, Stroke, TextStyle, Ui, Widget};
+
+/// How the remaining time of a timer is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountdownFormat {
+    /// `00:42`, hours are added to the minutes.
+    #[default]
+    MinutesSeconds,
+    /// `00:00:42`
+    HoursMinutesSeconds,
+    /// `42s`
+    Seconds,
+}
+
+impl CountdownFormat {
+    /// Formats the duration, rounding up to whole seconds so the countdown
+    /// only reaches zero when the time is actually up.
+    pub fn format(&self, remaining: Duration) -> String {
+        let millis = remaining.num_milliseconds().max(0);
+        let seconds = (millis + 999) / 1000;
+        match self {
+            CountdownFormat::MinutesSeconds => {
+                format!("{:02}:{:02}", seconds / 60, seconds % 60)
+            }
+            CountdownFormat::HoursMinutesSeconds => format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+            CountdownFormat::Seconds => format!("{seconds}s"),
+        }
+    }
+}
+
+/// Circular ring that is full at `1.0` and empty at `0.0`, filling clockwise
+/// from the top.
+pub struct TimerRing {
+    fraction: f32,
+    size: Option<f32>,
+    color: Option<Color32>,
+    text: Option<String>,
+}
+
+impl TimerRing {
+    pub fn new(fraction: f32) -> Self {
+        Self {
+            fraction: fraction.clamp(0., 1.),
+            size: None,
+            color: None,
+            text: None,
+        }
+    }
+    /// Diameter of the ring, defaults to twice the interact height.
+    #[must_use]
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+    /// Color of the filled part, defaults to the selection color.
+    #[must_use]
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+    /// Text shown in the middle of the ring.
+    #[must_use]
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+}
+
+impl Widget for TimerRing {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            fraction,
+            size,
+            color,
+            text,
+        } = self;
+        let size = size.unwrap_or_else(|| ui.spacing().interact_size.y * 2.);
+        let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::hover());
+        if !ui.is_rect_visible(rect) {
+            return response;
+        }
+
+        let visuals = ui.visuals();
+        let width = (size / 10.).max(2.);
+        let radius = (size - width) / 2.;
+        let center = rect.center();
+        let color = color.unwrap_or(visuals.selection.bg_fill);
+
+        let painter = ui.painter();
+        painter.circle_stroke(
+            center,
+            radius,
+            Stroke::new(width, visuals.widgets.inactive.bg_fill),
+        );
+        if fraction > 0. {
+            let segments = ((64. * fraction).ceil() as usize).max(2);
+            let points = (0..=segments)
+                .map(|i| {
+                    let angle = TAU * fraction * i as f32 / segments as f32 - TAU / 4.;
+                    pos2(
+                        center.x + radius * angle.cos(),
+                        center.y + radius * angle.sin(),
+                    )
+                })
+                .collect();
+            painter.add(Shape::line(points, Stroke::new(width, color)));
+        }
+        if let Some(text) = text {
+            painter.text(
+                center,
+                Align2::CENTER_CENTER,
+                text,
+                TextStyle::Small.resolve(ui.style()),
+                visuals.text_color(),
+            );
+        }
+        response
+    }
+}
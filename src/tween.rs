@@ -0,0 +1,179 @@
+//! Values animated towards a target over time, stored in the [`UiStates`]
+//! instead of the egui memory, so they live as long as the states do.
+//!
+//! ```
+//! let target = if self.expanded { 300. } else { 100. };
+//! let width = self.ui.tween(
+//!     ui.ctx(),
+//!     "panel_width",
+//!     target,
+//!     Duration::milliseconds(250),
+//!     Easing::CubicInOut,
+//! );
+//! ```
+//!
+//! Changing the target while animating starts a new animation from the
+//! current value, so there are no jumps.
+
+use chrono::{DateTime, Duration, Local};
+use egui::{emath::easing, lerp, Color32, Context, Rect, Vec2};
+
+use crate::{timer::TimerState, UiStates};
+
+/// Values that can be animated by [`Tween::tween`].
+pub trait Tweenable
+where
+    Self: Copy + PartialEq + Send + 'static,
+{
+    /// Value between `from` and `to`, `t` going from `0.0` to `1.0` but
+    /// possibly overshooting with some easing curves.
+    fn interpolate(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        lerp(from..=to, t)
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Tweenable for Color32 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.lerp_to_gamma(to, t.clamp(0., 1.))
+    }
+}
+
+impl Tweenable for Rect {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        Rect::from_min_max(
+            from.min + (to.min - from.min) * t,
+            from.max + (to.max - from.max) * t,
+        )
+    }
+}
+
+/// Curve applied to the linear progress of a tween.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadraticIn,
+    QuadraticOut,
+    QuadraticInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SinInOut,
+    ExponentialOut,
+    BackOut,
+    BounceOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => easing::linear(t),
+            Easing::QuadraticIn => easing::quadratic_in(t),
+            Easing::QuadraticOut => easing::quadratic_out(t),
+            Easing::QuadraticInOut => easing::quadratic_in_out(t),
+            Easing::CubicIn => easing::cubic_in(t),
+            Easing::CubicOut => easing::cubic_out(t),
+            Easing::CubicInOut => easing::cubic_in_out(t),
+            Easing::SinInOut => easing::sin_in_out(t),
+            Easing::ExponentialOut => easing::exponential_out(t),
+            Easing::BackOut => easing::back_out(t),
+            Easing::BounceOut => easing::bounce_out(t),
+        }
+    }
+}
+
+pub trait Tween {
+    /// Returns the current value of the tween, animating towards `target`.
+    /// The first call under a name starts at the target without animating.
+    ///
+    /// Requests repaints while animating.
+    fn tween<T>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        target: T,
+        duration: Duration,
+        easing: Easing,
+    ) -> T
+    where
+        T: Tweenable;
+}
+
+impl Tween for UiStates {
+    fn tween<T>(
+        &mut self,
+        ctx: &Context,
+        name: impl Into<String>,
+        target: T,
+        duration: Duration,
+        easing: Easing,
+    ) -> T
+    where
+        T: Tweenable,
+    {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), TweenState::new(target, duration));
+        state.easing = easing;
+
+        if state.to != target {
+            state.from = state.value(now);
+            state.to = target;
+            state.timer.set_duration(duration);
+            state.timer.start(now);
+        }
+        state.timer.finish_if_elapsed(now);
+
+        if state.timer.is_running() {
+            ctx.request_repaint();
+        }
+        state.value(now)
+    }
+}
+
+struct TweenState<T>
+where
+    T: Tweenable,
+{
+    from: T,
+    to: T,
+    easing: Easing,
+    /// Runs while animating from `from` to `to`.
+    timer: TimerState,
+}
+
+impl<T> TweenState<T>
+where
+    T: Tweenable,
+{
+    fn new(value: T, duration: Duration) -> Self {
+        Self {
+            from: value,
+            to: value,
+            easing: Easing::default(),
+            timer: TimerState::from(duration),
+        }
+    }
+    fn value(&self, now: DateTime<Local>) -> T {
+        let Some(remaining) = self.timer.remaining(now) else {
+            return self.to;
+        };
+        let duration = self.timer.duration();
+        if duration <= Duration::zero() {
+            return self.to;
+        }
+        let progress =
+            1. - remaining.num_milliseconds() as f32 / duration.num_milliseconds() as f32;
+        T::interpolate(self.from, self.to, self.easing.apply(progress))
+    }
+}
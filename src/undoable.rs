@@ -0,0 +1,298 @@
+//! Values with an undo and redo history, recording a snapshot whenever the
+//! value changed.
+//!
+//! ```
+//! let mut drawing = self.ui.undoable::<Drawing>("drawing");
+//! ui.horizontal(|ui| {
+//!     if ui.add_enabled(drawing.can_undo(), Button::new("undo")).clicked() {
+//!         drawing.undo();
+//!     }
+//!     if ui.add_enabled(drawing.can_redo(), Button::new("redo")).clicked() {
+//!         drawing.redo();
+//!     }
+//! });
+//! drawing.show(ui, |ui, drawing| {
+//!     ui.add(Slider::new(&mut drawing.stroke_width, 1.0..=10.0));
+//!     ui.text_edit_singleline(&mut drawing.title);
+//! });
+//! ```
+//!
+//! Changes made in quick succession, like typing or dragging a slider, are
+//! coalesced into a single step. Ctrl+Z and Ctrl+Shift+Z (Cmd on mac) undo
+//! and redo while a widget inside of [`show`][Undoable::show] has focus, or
+//! while nothing has focus and the pointer is over it.
+
+use chrono::{DateTime, Duration, Local};
+use egui::{Key, Modifiers, Rect, Ui};
+
+use crate::{timer::TimerState, UiStates, UserStateTraits};
+
+const DEFAULT_COALESCE_MILLIS: i64 = 500;
+const DEFAULT_HISTORY_LEN: usize = 100;
+
+pub trait CreateUndoable {
+    /// Starts with the default value and no history.
+    #[must_use]
+    fn undoable<T>(&mut self, name: impl Into<String>) -> Undoable<T>
+    where
+        T: UserStateTraits + Clone + PartialEq;
+}
+
+impl CreateUndoable for UiStates {
+    fn undoable<T>(&mut self, name: impl Into<String>) -> Undoable<T>
+    where
+        T: UserStateTraits + Clone + PartialEq,
+    {
+        let now = self.clock.now();
+        let state = self.get_mut(name.into(), UndoState::default());
+        Undoable { state, now }
+    }
+}
+
+/// Value and history of an undoable state.
+pub struct Undoable<'state, T>
+where
+    T: UserStateTraits + Clone + PartialEq,
+{
+    state: &'state mut UndoState<T>,
+    now: DateTime<Local>,
+}
+
+impl<'state, T> Undoable<'state, T>
+where
+    T: UserStateTraits + Clone + PartialEq,
+{
+    /// Changes within this time of the previous one are merged into the same
+    /// step, defaults to 500 milliseconds.
+    #[must_use]
+    pub fn coalesce(self, window: Duration) -> Self {
+        self.state.timer.set_duration(window);
+        self
+    }
+    /// Number of steps that can be undone, defaults to 100.
+    #[must_use]
+    pub fn max_history(self, max_history: usize) -> Self {
+        self.state.max_history = max_history;
+        self.state.trim();
+        self
+    }
+    pub fn value(&self) -> &T {
+        &self.state.value
+    }
+    /// Changes made through this are recorded on the next call to
+    /// [`show`][Undoable::show], [`undo`][Undoable::undo] or
+    /// [`redo`][Undoable::redo].
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.state.value
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.state.undo.is_empty() || self.state.value != self.state.present
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.state.redo.is_empty()
+    }
+    pub fn undo(&mut self) {
+        self.state.record(self.now);
+        let Some(previous) = self.state.undo.pop() else {
+            return;
+        };
+        let present = std::mem::replace(&mut self.state.present, previous.clone());
+        self.state.redo.push(present);
+        self.state.value = previous;
+        self.state.timer.stop();
+    }
+    pub fn redo(&mut self) {
+        self.state.record(self.now);
+        let Some(next) = self.state.redo.pop() else {
+            return;
+        };
+        let present = std::mem::replace(&mut self.state.present, next.clone());
+        self.state.undo.push(present);
+        self.state.value = next;
+        self.state.timer.stop();
+    }
+    /// Drops all steps, keeping the current value.
+    pub fn clear_history(&mut self) {
+        self.state.record(self.now);
+        self.state.undo.clear();
+        self.state.redo.clear();
+    }
+    /// Shows the contents editing the value and records the changes they
+    /// made. Handles the undo and redo shortcuts before the contents, so
+    /// text edits inside do not apply their own undo.
+    pub fn show<R>(&mut self, ui: &mut Ui, add_contents: impl FnOnce(&mut Ui, &mut T) -> R) -> R {
+        if self.has_focus(ui) {
+            let (redo, undo) = ui.input_mut(|input| {
+                (
+                    input.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z),
+                    input.consume_key(Modifiers::COMMAND, Key::Z),
+                )
+            });
+            if redo {
+                self.redo();
+            } else if undo {
+                self.undo();
+            }
+        }
+        let response = ui.scope(|ui| add_contents(ui, &mut self.state.value));
+        self.state.rect = Some(response.response.rect);
+        self.state.record(self.now);
+        response.inner
+    }
+    /// Uses the area of the contents from the previous frame.
+    fn has_focus(&self, ui: &Ui) -> bool {
+        let Some(rect) = self.state.rect else {
+            return false;
+        };
+        match ui.memory(|memory| memory.focused()) {
+            Some(id) => ui
+                .ctx()
+                .read_response(id)
+                .is_some_and(|response| rect.contains_rect(response.rect)),
+            None => ui.rect_contains_pointer(rect),
+        }
+    }
+}
+
+struct UndoState<T>
+where
+    T: UserStateTraits + Clone + PartialEq,
+{
+    value: T,
+    /// Last recorded snapshot of the value.
+    present: T,
+    undo: Vec<T>,
+    redo: Vec<T>,
+    max_history: usize,
+    /// Running while further changes are merged into the last step.
+    timer: TimerState,
+    /// Area of the contents, for the shortcuts.
+    rect: Option<Rect>,
+}
+
+impl<T> Default for UndoState<T>
+where
+    T: UserStateTraits + Clone + PartialEq,
+{
+    fn default() -> Self {
+        Self {
+            value: T::default(),
+            present: T::default(),
+            undo: vec![],
+            redo: vec![],
+            max_history: DEFAULT_HISTORY_LEN,
+            timer: TimerState::from(Duration::milliseconds(DEFAULT_COALESCE_MILLIS)),
+            rect: None,
+        }
+    }
+}
+
+impl<T> UndoState<T>
+where
+    T: UserStateTraits + Clone + PartialEq,
+{
+    /// Takes a snapshot if the value changed since the last one, merging it
+    /// into the last step if that was taken only moments ago.
+    fn record(&mut self, now: DateTime<Local>) {
+        self.timer.finish_if_elapsed(now);
+        if self.value == self.present {
+            return;
+        }
+        if self.timer.is_running() {
+            self.present = self.value.clone();
+        } else {
+            let previous = std::mem::replace(&mut self.present, self.value.clone());
+            self.undo.push(previous);
+            self.trim();
+        }
+        self.redo.clear();
+        self.timer.start(now);
+    }
+    fn trim(&mut self) {
+        let excess = self.undo.len().saturating_sub(self.max_history);
+        self.undo.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use egui::Context;
+
+    use super::CreateUndoable;
+    use crate::{
+        test_support::{run_ui, ManualClock},
+        UiStates,
+    };
+
+    /// Sets the value through `show`, which records it.
+    fn edit(states: &mut UiStates, ctx: &Context, value: i32) {
+        run_ui(ctx, |ui| {
+            states
+                .undoable::<i32>("count")
+                .show(ui, |_, count| *count = value);
+        });
+    }
+
+    fn value(states: &mut UiStates) -> i32 {
+        *states.undoable::<i32>("count").value()
+    }
+
+    #[test]
+    fn undo_and_redo_step_through_the_history() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        edit(&mut states, &ctx, 1);
+        clock.advance(Duration::seconds(1));
+        edit(&mut states, &ctx, 2);
+
+        states.undoable::<i32>("count").undo();
+        assert_eq!(value(&mut states), 1);
+        states.undoable::<i32>("count").undo();
+        assert_eq!(value(&mut states), 0);
+        assert!(!states.undoable::<i32>("count").can_undo());
+
+        states.undoable::<i32>("count").redo();
+        assert_eq!(value(&mut states), 1);
+        states.undoable::<i32>("count").redo();
+        assert_eq!(value(&mut states), 2);
+        assert!(!states.undoable::<i32>("count").can_redo());
+    }
+
+    #[test]
+    fn editing_after_undo_drops_the_redo_steps() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        edit(&mut states, &ctx, 1);
+        clock.advance(Duration::seconds(1));
+        edit(&mut states, &ctx, 2);
+        states.undoable::<i32>("count").undo();
+        clock.advance(Duration::seconds(1));
+        edit(&mut states, &ctx, 3);
+
+        assert!(!states.undoable::<i32>("count").can_redo());
+        states.undoable::<i32>("count").undo();
+        assert_eq!(value(&mut states), 1);
+    }
+
+    #[test]
+    fn quick_changes_are_one_step() {
+        let clock = ManualClock::new();
+        let mut states = clock.states();
+        let ctx = Context::default();
+
+        edit(&mut states, &ctx, 1);
+        clock.advance(Duration::milliseconds(100));
+        edit(&mut states, &ctx, 2);
+        clock.advance(Duration::milliseconds(100));
+        edit(&mut states, &ctx, 3);
+
+        states.undoable::<i32>("count").undo();
+        assert_eq!(value(&mut states), 0);
+        assert!(!states.undoable::<i32>("count").can_undo());
+    }
+}
@@ -0,0 +1,416 @@
+//! Multi-step flows like onboarding or imports, keeping the current step, the
+//! data entered so far and which steps are valid.
+//!
+//! ```
+//! #[derive(Default)]
+//! struct Import {
+//!     path: String,
+//!     delimiter: char,
+//! }
+//!
+//! let client = self.client.clone();
+//! let finished = self
+//!     .ui
+//!     .wizard::<Import>("import", ["file", "columns", "confirm"])
+//!     .default()
+//!     .on_submit(move |step, import| {
+//!         // only the last step is sent to the server
+//!         (step == 2).then(|| ImmediateValuePromise::new(client.import(import.path.clone())))
+//!     })
+//!     .step_ui(|ui, wizard| {
+//!         match wizard.step_index() {
+//!             0 => {
+//!                 ui.text_edit_singleline(&mut wizard.data_mut().path);
+//!             }
+//!             1 => {
+//!                 // ...
+//!             }
+//!             _ => {
+//!                 ui.label(format!("import {}", wizard.data().path));
+//!             }
+//!         }
+//!         let valid = !wizard.data().path.is_empty();
+//!         wizard.set_can_advance(valid);
+//!     })
+//!     .show(ui);
+//! ```
+//!
+//! Submissions keep running while the wizard is not shown, as long as
+//! [`UiStates::poll`] or [`UiStates::drain_events`] is called, and move on to
+//! the next step once they succeed.
+
+use std::{any::Any, cmp::Ordering};
+
+use egui::{Button, RichText, Ui};
+use lazy_async_promise::ImmediateValuePromise;
+
+use crate::{
+    clock::SharedClock,
+    events::StateEvent,
+    future_await::{FutureState, FutureStatusBuilder},
+    strings::{LightStatesStrings, TextKey},
+    style::LightStatesStyle,
+    UiStates, UserStateTraits,
+};
+
+pub trait CreateWizard {
+    /// If the steps differ from the ones stored under this name, the wizard
+    /// is reset to the new steps.
+    #[must_use]
+    fn wizard<T>(
+        &mut self,
+        name: impl Into<String>,
+        steps: impl IntoIterator<Item = impl Into<String>>,
+    ) -> WizardBuilder<T>
+    where
+        T: UserStateTraits;
+}
+
+impl CreateWizard for UiStates {
+    fn wizard<T>(
+        &mut self,
+        name: impl Into<String>,
+        steps: impl IntoIterator<Item = impl Into<String>>,
+    ) -> WizardBuilder<T>
+    where
+        T: UserStateTraits,
+    {
+        let name = name.into();
+        let steps: Vec<String> = steps.into_iter().map(Into::into).collect();
+        let strings = self.strings.clone();
+        let style = self.style.clone();
+        self.register_poller(name.clone(), poll_wizard_state::<T>);
+        let (state, events) = self.get_mut_with_events(name.clone(), WizardState::default());
+        if state.steps != steps {
+            *state = WizardState::new(steps);
+        }
+        WizardBuilder {
+            name,
+            wizard: Wizard {
+                state,
+                on_submit: None,
+            },
+            events,
+            strings,
+            style,
+            header_ui: None,
+            step_ui: None,
+            footer_ui: None,
+        }
+    }
+}
+
+type OnSubmit<T> = Box<dyn FnMut(usize, &mut T) -> Option<ImmediateValuePromise<()>>>;
+// gets the style when shown, so it can be overridden after `default`
+type WizardUi<T> = Box<dyn FnOnce(&mut Ui, &mut Wizard<T>, &LightStatesStyle)>;
+
+pub struct WizardBuilder<'state, T>
+where
+    T: UserStateTraits,
+{
+    name: String,
+    wizard: Wizard<'state, T>,
+    events: &'state mut Vec<StateEvent>,
+    strings: LightStatesStrings,
+    style: LightStatesStyle,
+    header_ui: Option<WizardUi<T>>,
+    step_ui: Option<WizardUi<T>>,
+    footer_ui: Option<WizardUi<T>>,
+}
+
+impl<'state, T> WizardBuilder<'state, T>
+where
+    T: UserStateTraits,
+{
+    /// Numbered step indicator as the header, where already reached steps
+    /// can be clicked, and back and next buttons as the footer.
+    #[must_use]
+    pub fn default(mut self) -> Self {
+        let strings = self.strings.clone();
+        self.header_ui = Some(Box::new(|ui, wizard, style| {
+            let current = wizard.step_index();
+            let mut clicked = None;
+            ui.horizontal(|ui| {
+                for (index, step) in wizard.steps().iter().enumerate() {
+                    if index > 0 {
+                        ui.label(RichText::new("›").weak());
+                    }
+                    let text = format!("{}. {}", index + 1, step);
+                    let text = match index.cmp(&current) {
+                        Ordering::Less => style.success_text(ui, text),
+                        Ordering::Equal => RichText::new(text).strong(),
+                        Ordering::Greater => RichText::new(text).weak(),
+                    };
+                    let button = Button::new(text).frame(false);
+                    if ui.add_enabled(wizard.can_jump(index), button).clicked() {
+                        clicked = Some(index);
+                    }
+                }
+            });
+            ui.separator();
+            if let Some(index) = clicked {
+                wizard.jump(index);
+            }
+        }));
+        self.footer_ui(move |ui, wizard| {
+            ui.horizontal(|ui| {
+                let can_back = wizard.step_index() > 0 && !wizard.is_submitting();
+                if ui
+                    .add_enabled(can_back, Button::new(strings.get(TextKey::Back)))
+                    .clicked()
+                {
+                    wizard.back();
+                }
+                let next = if wizard.is_last() {
+                    strings.get(TextKey::Finish)
+                } else {
+                    strings.get(TextKey::Next)
+                };
+                if ui
+                    .add_enabled(wizard.can_advance(), Button::new(next))
+                    .clicked()
+                {
+                    wizard.next();
+                }
+            });
+        })
+    }
+    /// Overrides the [`LightStatesStyle`] of the [`UiStates`] for this call.
+    #[must_use]
+    pub fn style(mut self, style: LightStatesStyle) -> Self {
+        self.style = style;
+        self
+    }
+    /// Called by [`Wizard::next`] with the index of the step being left and
+    /// the data. Returning a future submits the step, the wizard only moves
+    /// on once the future succeeded. Returning `None` moves on right away.
+    #[must_use]
+    pub fn on_submit(
+        mut self,
+        on_submit: impl FnMut(usize, &mut T) -> Option<ImmediateValuePromise<()>> + 'static,
+    ) -> Self {
+        self.wizard.on_submit = Some(Box::new(on_submit));
+        self
+    }
+    /// Shown above the step.
+    #[must_use]
+    pub fn header_ui(mut self, header_ui: impl FnOnce(&mut Ui, &mut Wizard<T>) + 'static) -> Self {
+        self.header_ui = Some(Box::new(move |ui, wizard, _| header_ui(ui, wizard)));
+        self
+    }
+    /// Contents of the current step, should update the validity of the step
+    /// through [`Wizard::set_can_advance`].
+    #[must_use]
+    pub fn step_ui(mut self, step_ui: impl FnOnce(&mut Ui, &mut Wizard<T>) + 'static) -> Self {
+        self.step_ui = Some(Box::new(move |ui, wizard, _| step_ui(ui, wizard)));
+        self
+    }
+    /// Shown below the step and the status of a running submission.
+    #[must_use]
+    pub fn footer_ui(mut self, footer_ui: impl FnOnce(&mut Ui, &mut Wizard<T>) + 'static) -> Self {
+        self.footer_ui = Some(Box::new(move |ui, wizard, _| footer_ui(ui, wizard)));
+        self
+    }
+    /// Returns `true` once, on the call after the last step was completed.
+    pub fn show(self, ui: &mut Ui) -> bool {
+        let Self {
+            name,
+            mut wizard,
+            events,
+            strings,
+            style,
+            header_ui,
+            step_ui,
+            footer_ui,
+        } = self;
+        wizard.state.poll(&name, events);
+        if let Some(header_ui) = header_ui {
+            header_ui(ui, &mut wizard, &style);
+        }
+        if let Some(step_ui) = step_ui {
+            step_ui(ui, &mut wizard, &style);
+        }
+        FutureStatusBuilder::new(
+            name,
+            &mut wizard.state.submission,
+            events,
+            strings,
+            style.clone(),
+        )
+        .default()
+        .show(ui);
+        // the status UI might have seen the submission finish, so the wizard
+        // only moves on in the next frame
+        if wizard.state.submitting && !wizard.state.submission.is_running() {
+            ui.ctx().request_repaint();
+        }
+        if let Some(footer_ui) = footer_ui {
+            footer_ui(ui, &mut wizard, &style);
+        }
+        std::mem::take(&mut wizard.state.completed)
+    }
+}
+
+/// Current step and data of a wizard, handed to its UIs.
+pub struct Wizard<'state, T>
+where
+    T: UserStateTraits,
+{
+    state: &'state mut WizardState<T>,
+    on_submit: Option<OnSubmit<T>>,
+}
+
+impl<'state, T> Wizard<'state, T>
+where
+    T: UserStateTraits,
+{
+    pub fn steps(&self) -> &[String] {
+        &self.state.steps
+    }
+    pub fn step_index(&self) -> usize {
+        self.state.current
+    }
+    pub fn step(&self) -> Option<&str> {
+        self.state.steps.get(self.state.current).map(String::as_str)
+    }
+    pub fn is_last(&self) -> bool {
+        self.state.current + 1 >= self.state.steps.len()
+    }
+    pub fn data(&self) -> &T {
+        &self.state.data
+    }
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.state.data
+    }
+    /// Marks the current step as valid or not, steps are valid until set
+    /// otherwise.
+    pub fn set_can_advance(&mut self, can_advance: bool) {
+        if let Some(valid) = self.state.valid.get_mut(self.state.current) {
+            *valid = can_advance;
+        }
+    }
+    /// `true` if the current step is valid and not being submitted.
+    pub fn can_advance(&self) -> bool {
+        self.state.is_valid(self.state.current) && !self.is_submitting()
+    }
+    pub fn is_submitting(&self) -> bool {
+        self.state.submission.is_running()
+    }
+    /// Submits the current step through the [`on_submit`][WizardBuilder::on_submit]
+    /// function and moves on to the next step, or completes the wizard on the
+    /// last one. Does nothing if the step can not advance.
+    pub fn next(&mut self) {
+        if !self.can_advance() {
+            return;
+        }
+        let current = self.state.current;
+        let promise = self
+            .on_submit
+            .as_mut()
+            .and_then(|on_submit| on_submit(current, &mut self.state.data));
+        match promise {
+            Some(promise) => {
+                self.state.submission.start(promise);
+                self.state.submitting = true;
+            }
+            None => self.state.advance(),
+        }
+    }
+    pub fn back(&mut self) {
+        if self.state.current > 0 {
+            self.jump(self.state.current - 1);
+        }
+    }
+    /// `true` for earlier steps and for already reached later steps, as long
+    /// as all steps in between are valid.
+    pub fn can_jump(&self, index: usize) -> bool {
+        if self.is_submitting() || index >= self.state.steps.len() {
+            return false;
+        }
+        index <= self.state.current
+            || (index <= self.state.furthest
+                && (self.state.current..index).all(|step| self.state.is_valid(step)))
+    }
+    /// Goes to the step if [`can_jump`][Wizard::can_jump] allows it, without
+    /// submitting the steps in between.
+    pub fn jump(&mut self, index: usize) {
+        if self.can_jump(index) {
+            self.state.submission.clear();
+            self.state.current = index;
+        }
+    }
+    /// Goes back to the first step and resets the data.
+    pub fn reset(&mut self) {
+        let steps = std::mem::take(&mut self.state.steps);
+        *self.state = WizardState::new(steps);
+    }
+}
+
+#[derive(Default)]
+struct WizardState<T>
+where
+    T: UserStateTraits,
+{
+    steps: Vec<String>,
+    current: usize,
+    /// Last step that was reached by advancing.
+    furthest: usize,
+    valid: Vec<bool>,
+    /// Set when the last step is completed, until it is returned by
+    /// [`WizardBuilder::show`].
+    completed: bool,
+    data: T,
+    /// Submission of the current step.
+    submission: FutureState<()>,
+    /// Set from submitting until the submission was seen finishing, the
+    /// status UI polls the submission as well.
+    submitting: bool,
+}
+
+impl<T> WizardState<T>
+where
+    T: UserStateTraits,
+{
+    fn new(steps: Vec<String>) -> Self {
+        Self {
+            valid: vec![true; steps.len()],
+            steps,
+            ..Default::default()
+        }
+    }
+    fn is_valid(&self, step: usize) -> bool {
+        self.valid.get(step).copied().unwrap_or(false)
+    }
+    fn advance(&mut self) {
+        if self.current + 1 < self.steps.len() {
+            self.current += 1;
+            self.furthest = self.furthest.max(self.current);
+        } else {
+            self.completed = true;
+        }
+    }
+    /// Moves on to the next step once the submission succeeded.
+    fn poll(&mut self, name: &str, events: &mut Vec<StateEvent>) {
+        self.submission.poll(name, events);
+        if self.submitting && !self.submission.is_running() {
+            self.submitting = false;
+            if self.submission.succeeded() {
+                self.submission.clear();
+                self.advance();
+            }
+        }
+    }
+}
+
+fn poll_wizard_state<T>(
+    name: &str,
+    state: &mut (dyn Any + Send),
+    _: &SharedClock,
+    events: &mut Vec<StateEvent>,
+) where
+    T: UserStateTraits,
+{
+    if let Some(state) = state.downcast_mut::<WizardState<T>>() {
+        state.poll(name, events);
+    }
+}